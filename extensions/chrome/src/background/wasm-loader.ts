@@ -71,17 +71,13 @@ export class ChromeWASMLoader {
 
         // Adapter: if we accidentally received the raw wasm namespace instead of the wrapper
         const candidate: any = this.wasmModule;
-        if (typeof candidate?.createStreamingAnalyzer !== 'function' && typeof candidate?.WasmModule === 'function') {
+        if (typeof candidate?.createStreamingAnalyzer !== 'function' && typeof candidate?.StreamingSession === 'function') {
           console.warn('[BG] ChromeWASMLoader: building adapter around raw WasmModule namespace');
           const makeAdapter = (ns: any) => ({
-            createStreamingAnalyzer: (config?: any) => {
-              const mod = new ns.WasmModule();
-              const analyzer = config ? mod.init_streaming_with_config(config) : mod.init_streaming();
-              return { __module: mod, __analyzer: analyzer };
-            },
-            processChunk: (handle: any, chunk: string) => handle.__module.process_chunk(handle.__analyzer, chunk),
+            createStreamingAnalyzer: (config?: any) => new ns.StreamingSession(config),
+            processChunk: (handle: any, chunk: string) => handle.push_chunk(chunk),
             finalizeStreaming: (handle: any) => {
-              const raw = handle.__module.finalize_streaming(handle.__analyzer);
+              const raw = handle.finalize();
               return {
                 topWords: raw?.top_words ?? [],
                 bannedPhrases: raw?.banned_phrases ?? [],
@@ -93,7 +89,8 @@ export class ChromeWASMLoader {
                 riskScore: raw?.risk_score ?? 0,
               };
             },
-            getStreamingStats: (handle: any) => handle.__module.get_streaming_stats(handle.__analyzer),
+            getStreamingStats: (handle: any) => handle.stats(),
+            freeStreamingAnalyzer: (handle: any) => handle.free(),
           });
           this.wasmModule = makeAdapter(candidate);
           console.log('[BG] ChromeWASMLoader: adapter installed; typeof createStreamingAnalyzer =', typeof (this.wasmModule as any)?.createStreamingAnalyzer);
@@ -196,6 +193,7 @@ class RealStreamingAnalyzer implements StreamingAnalyzer {
     try {
       const result = this.wasmModule.finalizeStreaming(this.handle);
       const stats = this.wasmModule.getStreamingStats(this.handle);
+      this.free();
       
       const finalResult = {
         ...result,
@@ -220,6 +218,7 @@ class RealStreamingAnalyzer implements StreamingAnalyzer {
   reset(): void {
     this.chunks = [];
     this.totalSize = 0;
+    this.free();
     
     try {
       this.handle = this.wasmModule.createStreamingAnalyzer();
@@ -228,6 +227,19 @@ class RealStreamingAnalyzer implements StreamingAnalyzer {
       throw new Error(`WASM analyzer reset failed: ${(error as Error).message}`);
     }
   }
+
+  // Release the WASM session behind the current handle
+  private free(): void {
+    if (!this.handle) {
+      return;
+    }
+    try {
+      this.wasmModule.freeStreamingAnalyzer(this.handle);
+    } catch (error) {
+      console.warn('Failed to free WASM analyzer:', error);
+    }
+    this.handle = null;
+  }
 }
 
 export const chromeWASMLoader = new ChromeWASMLoader();
@@ -48,17 +48,13 @@ export class FirefoxWASMLoader {
 
         // Adapter in case we received raw wasm namespace instead of wrapper
         const candidate: any = this.wasmModule;
-        if (typeof candidate?.createStreamingAnalyzer !== 'function' && typeof candidate?.WasmModule === 'function') {
+        if (typeof candidate?.createStreamingAnalyzer !== 'function' && typeof candidate?.StreamingSession === 'function') {
           console.warn('[FF] Building adapter around raw WasmModule namespace');
           const makeAdapter = (ns: any) => ({
-            createStreamingAnalyzer: (config?: any) => {
-              const mod = new ns.WasmModule();
-              const analyzer = config ? mod.init_streaming_with_config(config) : mod.init_streaming();
-              return { __module: mod, __analyzer: analyzer };
-            },
-            processChunk: (handle: any, chunk: string) => handle.__module.process_chunk(handle.__analyzer, chunk),
+            createStreamingAnalyzer: (config?: any) => new ns.StreamingSession(config),
+            processChunk: (handle: any, chunk: string) => handle.push_chunk(chunk),
             finalizeStreaming: (handle: any) => {
-              const raw = handle.__module.finalize_streaming(handle.__analyzer);
+              const raw = handle.finalize();
               return {
                 topWords: raw?.top_words ?? [],
                 bannedPhrases: raw?.banned_phrases ?? [],
@@ -70,7 +66,8 @@ export class FirefoxWASMLoader {
                 riskScore: raw?.risk_score ?? 0,
               };
             },
-            getStreamingStats: (handle: any) => handle.__module.get_streaming_stats(handle.__analyzer),
+            getStreamingStats: (handle: any) => handle.stats(),
+            freeStreamingAnalyzer: (handle: any) => handle.free(),
           });
           this.wasmModule = makeAdapter(candidate);
           console.log('[FF] Adapter installed; typeof createStreamingAnalyzer =', typeof (this.wasmModule as any)?.createStreamingAnalyzer);
@@ -201,59 +198,8 @@ export class StreamingAnalyzerWrapper {
       
       this.chunks.push(chunk);
       
-      // STEP 2: Use direct WASM interface like Chrome
-      // Get the raw WASM module instance
-      const rawModule = this.wasmModule.getRawModule?.() || this.wasmModule;
-      
-      if (rawModule && typeof rawModule.process_chunk === 'function') {
-        // Use direct WASM interface like Chrome
-        console.log('[FF] Using direct WASM interface for process_chunk');
-        
-        // STEP 3: Add proper handle validation before reassignment
-        const newHandle = rawModule.process_chunk(this.handle, chunk);
-        
-        // Validate the new handle before reassignment
-        if (newHandle && typeof newHandle === 'object') {
-          // Check if it's a valid analyzer handle
-          if (newHandle.__module && newHandle.__analyzer) {
-            this.handle = newHandle;
-            console.log('[FF] Direct WASM process_chunk completed, handle updated - valid structure');
-          } else if (typeof newHandle === 'object' && Object.keys(newHandle).length > 0) {
-            // It might be a direct analyzer reference
-            this.handle = newHandle;
-            console.log('[FF] Direct WASM process_chunk completed, handle updated - direct reference');
-          } else {
-            console.warn('[FF] Direct process_chunk returned invalid handle structure:', newHandle);
-            throw new Error('Invalid analyzer handle returned from direct WASM interface');
-          }
-        } else {
-          console.warn('[FF] Direct process_chunk returned invalid handle:', newHandle);
-          throw new Error('Invalid analyzer handle returned from direct WASM interface');
-        }
-      } else {
-        // Fallback to wrapper interface
-        console.log('[FF] Using wrapper interface for processChunk');
-        console.log('[FF] processChunk input - handle:', this.handle, 'chunk length:', chunk.length);
-        const result = this.wasmModule.processChunk(this.handle, chunk);
-        console.log('[FF] processChunk output - result:', result, 'result type:', typeof result);
-        
-        // Enhanced handle validation and reassignment
-        if (result && typeof result === 'object') {
-          // Validate the handle has the expected structure
-          if (result.__module && result.__analyzer) {
-            this.handle = result;
-            console.log('[FF] WASM analyzer handle updated after processChunk - valid structure');
-          } else if (typeof result === 'object' && Object.keys(result).length > 0) {
-            // Fallback: if it's an object but not the expected structure, it might be a direct analyzer
-            this.handle = result;
-            console.log('[FF] WASM analyzer handle updated after processChunk - fallback structure');
-          } else {
-            console.warn('[FF] processChunk returned unexpected result structure:', result);
-          }
-        } else {
-          console.warn('[FF] processChunk returned invalid result:', result);
-        }
-      }
+      console.log('[FF] processChunk input - handle:', this.handle, 'chunk length:', chunk.length);
+      this.wasmModule.processChunk(this.handle, chunk);
       
       console.log('[FF] WASM processChunk completed successfully');
     } catch (error) {
@@ -282,27 +228,14 @@ export class StreamingAnalyzerWrapper {
     });
     
     try {
-      // Check if the handle is actually a complete result from processChunk
-      if (this.handle && typeof this.handle === 'object' && 
-          (this.handle.total_content || this.handle.word_counts || this.handle.banned_phrase_matches)) {
-        console.log('[FF] Handle appears to be a complete result, skipping finalizeStreaming');
-        result = this.handle;
-      } else {
-        // FIXED: Call finalizeStreaming FIRST, then getStreamingStats
-        // The handle becomes invalid after finalizeStreaming, so we can't get stats after
-        console.log('[FF] About to call finalizeStreaming with handle:', {
-          handle: this.handle,
-          handleKeys: Object.keys(this.handle),
-          handleValues: Object.values(this.handle),
-          wasmModuleType: typeof this.wasmModule,
-          hasFinalizeStreaming: typeof this.wasmModule.finalizeStreaming === 'function'
-        });
-        
-        result = this.wasmModule.finalizeStreaming(this.handle);
-        console.log('[FF] finalizeStreaming succeeded, result:', result);
-        // Note: We can't get stats after finalizeStreaming because the handle is invalid
-        // We'll use default stats instead
-      }
+      console.log('[FF] About to call finalizeStreaming with handle:', {
+        handle: this.handle,
+        wasmModuleType: typeof this.wasmModule,
+        hasFinalizeStreaming: typeof this.wasmModule.finalizeStreaming === 'function'
+      });
+      
+      result = this.wasmModule.finalizeStreaming(this.handle);
+      console.log('[FF] finalizeStreaming succeeded, result:', result);
     } catch (error) {
       // Enhanced error logging for Firefox debugging
       console.error('[FF] Detailed WASM finalize error:', {
@@ -314,6 +247,10 @@ export class StreamingAnalyzerWrapper {
         timestamp: new Date().toISOString()
       });
       throw error;
+    } finally {
+      // Release the WASM session; the wrapper is not reused after finalize
+      this.wasmModule.freeStreamingAnalyzer(this.handle);
+      this.handle = null;
     }
     
     const endTime = Date.now();
@@ -113,6 +113,8 @@ class StreamingAnalyzerWrapper {
   finalize(): any {
     const result = this.wasmModule.finalizeStreaming(this.handle);
     const stats = this.wasmModule.getStreamingStats(this.handle);
+    this.wasmModule.freeStreamingAnalyzer(this.handle);
+    this.handle = null;
     const endTime = Date.now();
     const duration = endTime - this.startTime;
     
@@ -173,11 +173,11 @@ describe('Schema Validation', () => {
       max_words: 20,
       banned_phrases: ['confidential', 'secret'],
       custom_pii_patterns: [{
-        id: 'custom-1',
-        name: 'Custom Pattern',
-        regex: '\\d{3}-\\d{2}-\\d{4}',
-        type: 'ssn',
+        name: 'employee_id',
+        regex: '\\bEMP-\\d{6}\\b',
+        validator: { lengths: [9] },
         confidence: 0.9,
+        severity: 'high',
         enabled: true
       }],
       chunk_size: 1048576
@@ -207,10 +207,10 @@ describe('Schema Validation', () => {
       expect(() => validateStreamingConfig(invalid)).toThrow();
     });
 
-    test('should reject invalid custom PII pattern type', () => {
+    test('should reject invalid custom PII pattern severity', () => {
       const invalid = {
         ...validConfig,
-        custom_pii_patterns: [{ ...validConfig.custom_pii_patterns![0], type: 'invalid' as any }]
+        custom_pii_patterns: [{ ...validConfig.custom_pii_patterns![0], severity: 'invalid' as any }]
       };
       expect(() => validateStreamingConfig(invalid)).toThrow();
     });
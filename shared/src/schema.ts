@@ -17,22 +17,44 @@ import { z } from 'zod';
 interface WasmAnalysisResult {
   /** Overall risk score (0.0 - 1.0) */
   risk_score: number;
-  /** Final decision: 'allow' | 'block' */
-  decision: 'allow' | 'block';
-  /** List of reasons for the decision */
-  reasons: string[];
+  /** Final decision, from least to most restrictive */
+  decision: 'allow' | 'warn' | 'justify' | 'redact' | 'block';
+  /** Structured breakdown of the decision */
+  reasons: DecisionReason[];
   /** Word frequency analysis results */
   top_words: Array<[string, number]>;
   /** Detected banned phrases */
   banned_phrases: BannedPhraseMatch[];
   /** Detected PII patterns */
   pii_patterns: PIIPattern[];
+  /** Detected credentials */
+  secrets: SecretFinding[];
+  /** Composite rules that hold over the other findings */
+  composite_findings?: CompositeFinding[];
+  /** Findings suppressed by the allow-list, kept for audit */
+  suppressed_findings?: SuppressedFinding[];
   /** Calculated entropy value */
   entropy: number;
   /** Processing statistics */
   stats: ProcessingStats;
 }
 
+/**
+ * One entry of the breakdown explaining a decision
+ */
+interface DecisionReason {
+  /** Stable identifier, usable as a localization key */
+  code: 'always_block_rule' | 'banned_phrases' | 'pii_patterns' | 'secrets' | 'composite_rules' | 'entropy' | 'decision_threshold';
+  /** Detector responsible, or 'policy' for the decision threshold entry */
+  detector: string;
+  /** Amount added to the risk score */
+  contribution: number;
+  /** Threshold crossed, if any */
+  threshold?: { name: string; value: number; observed: number };
+  /** IDs of the findings behind this entry */
+  finding_ids: string[];
+}
+
 /**
  * Banned phrase match with context
  */
@@ -44,7 +66,46 @@ interface BannedPhraseMatch {
   /** Context around the match */
   context: string;
   /** Severity level of the banned phrase */
-  severity: 'low' | 'medium' | 'high';
+  severity: 'low' | 'medium' | 'high' | 'critical';
+  /** Matched only after normalizing disguised text, e.g. "c0nf1dential" */
+  evasion?: boolean;
+  /** Category label of the rule that matched */
+  category?: string;
+  /** Evidence the match adds to the banned phrase detector */
+  weight?: number;
+}
+
+/**
+ * Composite rule that holds, with the findings that satisfy it
+ */
+interface CompositeFinding {
+  /** Identifier referenced by reason finding_ids, e.g. 'composite-0' */
+  id: string;
+  /** Name of the rule */
+  rule: string;
+  /** Severity of the rule */
+  severity: 'low' | 'medium' | 'high' | 'critical';
+  /** Evidence the finding adds to the composite detector */
+  weight: number;
+  /** IDs of the banned phrase and PII findings that satisfy the rule */
+  finding_ids: string[];
+}
+
+/**
+ * Finding suppressed by an allow-list entry
+ */
+interface SuppressedFinding {
+  detector: 'banned_phrase' | 'pii' | 'secret';
+  /** PII or secret type, or banned phrase */
+  type_: string;
+  /** Matched text; masked for secrets */
+  pattern: string;
+  position: number;
+  confidence: number;
+  /** Index of the entry in allow_list */
+  allow_list_index: number;
+  /** Note of that entry */
+  note?: string;
 }
 
 /**
@@ -52,17 +113,50 @@ interface BannedPhraseMatch {
  */
 interface PIIPattern {
   /** Type of PII detected */
-  type: 'phone' | 'email' | 'ssn' | 'credit_card' | 'ip_address' | 'custom';
+  type: 'phone' | 'email' | 'ssn' | 'credit_card' | 'ip_address'
+    | 'uk_nino' | 'ca_sin' | 'fr_insee' | 'de_steuer_id' | 'it_codice_fiscale' | 'es_dni' | 'es_nie'
+    | 'nl_bsn' | 'in_aadhaar' | 'in_pan' | 'br_cpf' | 'br_cnpj'
+    | 'iban' | 'swift_bic' | 'aba_routing' | 'bank_account' | 'mac_address' | 'hostname' | 'custom';
   /** The detected pattern */
   pattern: string;
   /** Position in the text */
   position: number;
   /** Confidence score (0.0 - 1.0) */
   confidence: number;
+  /** Severity of a custom pattern finding */
+  severity?: 'low' | 'medium' | 'high' | 'critical';
+  /** Card network identified from the IIN, for credit cards */
+  card_network?: 'visa' | 'mastercard' | 'amex' | 'discover' | 'diners_club' | 'jcb' | 'unionpay' | 'maestro' | 'mir';
+  /** Address class, for IP addresses and internal hostnames */
+  classification?: 'public' | 'private' | 'loopback' | 'link_local' | 'documentation' | 'reserved';
+  /** Context keywords near the match that raised its confidence */
+  supporting_keywords?: string[];
+  /** Context keywords near the match that lowered its confidence */
+  negating_keywords?: string[];
+  /** Overlapping matches of other detectors that this finding outranked */
+  alternatives?: { type: string; pattern: string; position: number; confidence: number }[];
   /** Custom regex pattern if applicable */
   custom_regex?: string;
 }
 
+/**
+ * Credential detection result
+ */
+interface SecretFinding {
+  /** Type of credential detected */
+  type: 'aws_access_key_id' | 'aws_secret_access_key' | 'github_token' | 'gitlab_token' | 'slack_token'
+    | 'slack_webhook' | 'stripe_key' | 'google_api_key' | 'jwt' | 'private_key' | 'ssh_private_key'
+    | 'password' | 'generic_secret';
+  /** Start of the value followed by a mask; never the whole secret */
+  pattern: string;
+  /** Position in the text */
+  position: number;
+  /** Confidence score (0.0 - 1.0) */
+  confidence: number;
+  /** Shannon entropy of the value, for generic assignments */
+  entropy?: number;
+}
+
 /**
  * Processing statistics for streaming analysis
  */
@@ -77,6 +171,8 @@ interface ProcessingStats {
   banned_phrase_count: number;
   /** Number of PII patterns found */
   pii_pattern_count: number;
+  /** Number of secrets found */
+  secret_count: number;
   /** Processing time in milliseconds */
   processing_time_ms: number;
   /** Performance metrics */
@@ -173,30 +269,131 @@ interface StreamingConfig {
   risk_threshold: number;
   /** Maximum number of words to return in frequency analysis */
   max_words: number;
-  /** Phrases to detect as banned content */
-  banned_phrases: string[];
+  /** Banned phrase rules; a plain string is a case-insensitive whole-word literal */
+  banned_phrases: (string | BannedPhraseRule)[];
+  /** Normalization that matches disguised banned phrases */
+  phrase_normalization?: PhraseNormalization;
   /** Custom PII detection patterns */
   custom_pii_patterns?: CustomPIIPattern[];
+  /** Proximity and boolean rules over banned phrase and PII findings */
+  composite_rules?: CompositeRule[];
+  /** Known false positives, suppressed before scoring */
+  allow_list?: AllowListEntry[];
   /** Chunk size for processing (in bytes) */
   chunk_size?: number;
 }
 
+/**
+ * Banned phrase rule
+ */
+interface BannedPhraseRule {
+  /** Text, regex or glob to match */
+  pattern: string;
+  /** How the pattern is matched (default 'literal') */
+  type?: 'literal' | 'word' | 'regex' | 'glob';
+  /** Match case exactly (default false) */
+  case_sensitive?: boolean;
+  /** Only match whole words (default true) */
+  whole_word?: boolean;
+  /** Severity reported with each match (default 'high') */
+  severity?: 'low' | 'medium' | 'high' | 'critical';
+  /** Label reported with each match */
+  category?: string;
+  /** Evidence each match adds to the risk score (default 1) */
+  weight?: number;
+}
+
+/**
+ * Steps applied to banned phrases and text before matching
+ */
+interface PhraseNormalization {
+  /** Match phrases through the steps below (default false) */
+  enabled?: boolean;
+  /** Decompose fullwidth, mathematical, circled and ligature forms as NFKC does */
+  compatibility?: boolean;
+  /** Replace non-ASCII characters by their UTS #39 confusable skeleton */
+  confusables?: boolean;
+  /** Read digits and symbols standing in for letters, any of several for 1, 6, 7 and 9 */
+  leetspeak?: boolean;
+  /** Ignore spaces, punctuation and zero-width characters between letters */
+  separators?: boolean;
+}
+
+/**
+ * Banned phrase or PII findings a composite condition refers to
+ */
+interface FindingSelector {
+  detector: 'banned_phrase' | 'pii';
+  /** Banned phrase rule pattern or PII type; any when omitted */
+  type?: string;
+  /** Banned phrase rule category; any when omitted */
+  category?: string;
+  min_confidence?: number;
+  /** Default 1; within near, counts findings near the other term */
+  min_count?: number;
+}
+
+/**
+ * Condition over the findings of the whole document
+ */
+type CompositeCondition =
+  | { finding: FindingSelector }
+  | { near: { terms: [FindingSelector, FindingSelector]; within: number } }
+  | { and: CompositeCondition[] }
+  | { or: CompositeCondition[] }
+  | { not: CompositeCondition };
+
+/**
+ * Proximity and boolean rule producing a composite finding
+ */
+interface CompositeRule {
+  /** Unique name, reported as the finding's rule */
+  name: string;
+  condition: CompositeCondition;
+  /** Severity reported with each finding (default 'high') */
+  severity?: 'low' | 'medium' | 'high' | 'critical';
+  /** Evidence each finding adds to the risk score (default 1) */
+  weight?: number;
+}
+
+/**
+ * Allow-list entry; exactly one of value, sha256, regex or context is set
+ */
+interface AllowListEntry {
+  /** Allowed value, compared on its letters and digits, ignoring case */
+  value?: string;
+  /** Hex SHA-256 of an allowed value's lowercased letters and digits */
+  sha256?: string;
+  /** Regex the whole matched text must match */
+  regex?: string;
+  /** Phrase within 64 bytes of the finding, or around it */
+  context?: string;
+  /** Detector the entry applies to; any when omitted */
+  detector?: 'banned_phrase' | 'pii' | 'secret';
+  /** PII or secret type, or banned phrase; any when omitted */
+  type?: string;
+  /** Reported with the findings the entry suppresses */
+  note?: string;
+}
+
 /**
  * Custom PII detection pattern
  */
 interface CustomPIIPattern {
-  /** Unique identifier for the pattern */
-  id: string;
-  /** Human-readable name */
+  /** Reported as the finding type; lowercase letters, digits and underscores */
   name: string;
-  /** Regular expression pattern */
+  /** Regular expression pattern; repetitions must be bounded */
   regex: string;
-  /** Type of PII this pattern detects */
-  type: 'phone' | 'email' | 'ssn' | 'credit_card' | 'ip_address' | 'custom';
-  /** Confidence score (0.0 - 1.0) */
-  confidence: number;
-  /** Whether this pattern is enabled */
-  enabled: boolean;
+  /** Check a match must pass to be reported */
+  validator?: 'luhn' | 'mod97' | { lengths: number[] };
+  /** Confidence score (0.0 - 1.0), default 0.8 */
+  confidence?: number;
+  /** Context keywords that raise or lower the confidence */
+  keywords?: { supporting?: string[]; negating?: string[] };
+  /** Severity reported with each finding, default 'medium' */
+  severity?: 'low' | 'medium' | 'high' | 'critical';
+  /** Whether this pattern is enabled, default true */
+  enabled?: boolean;
 }
 
 // ============================================================================
@@ -565,22 +762,74 @@ const PerformanceMetricsSchema = z.object({
  */
 const WasmAnalysisResultSchema = z.object({
   risk_score: z.number().min(0).max(1),
-  decision: z.enum(['allow', 'block']),
-  reasons: z.array(z.string()),
+  decision: z.enum(['allow', 'warn', 'justify', 'redact', 'block']),
+  reasons: z.array(z.object({
+    code: z.enum(['always_block_rule', 'banned_phrases', 'pii_patterns', 'secrets', 'composite_rules', 'entropy', 'decision_threshold']),
+    detector: z.string(),
+    contribution: z.number(),
+    threshold: z.object({ name: z.string(), value: z.number(), observed: z.number() }).optional(),
+    finding_ids: z.array(z.string())
+  })),
   top_words: z.array(z.tuple([z.string(), z.number()])),
   banned_phrases: z.array(z.object({
     phrase: z.string(),
     position: z.number(),
     context: z.string(),
-    severity: z.enum(['low', 'medium', 'high'])
+    severity: z.enum(['low', 'medium', 'high', 'critical']),
+    evasion: z.boolean().optional(),
+    category: z.string().optional(),
+    weight: z.number().min(0).optional()
   })),
   pii_patterns: z.array(z.object({
-    type: z.enum(['phone', 'email', 'ssn', 'credit_card', 'ip_address', 'custom']),
+    type: z.enum([
+      'phone', 'email', 'ssn', 'credit_card', 'ip_address',
+      'uk_nino', 'ca_sin', 'fr_insee', 'de_steuer_id', 'it_codice_fiscale', 'es_dni', 'es_nie',
+      'nl_bsn', 'in_aadhaar', 'in_pan', 'br_cpf', 'br_cnpj',
+      'iban', 'swift_bic', 'aba_routing', 'bank_account', 'mac_address', 'hostname', 'custom'
+    ]),
     pattern: z.string(),
     position: z.number(),
     confidence: z.number().min(0).max(1),
+    card_network: z.enum(['visa', 'mastercard', 'amex', 'discover', 'diners_club', 'jcb', 'unionpay', 'maestro', 'mir']).optional(),
+    classification: z.enum(['public', 'private', 'loopback', 'link_local', 'documentation', 'reserved']).optional(),
+    severity: z.enum(['low', 'medium', 'high', 'critical']).optional(),
+    supporting_keywords: z.array(z.string()).optional(),
+    negating_keywords: z.array(z.string()).optional(),
+    alternatives: z.array(z.object({
+      type: z.string(),
+      pattern: z.string(),
+      position: z.number(),
+      confidence: z.number().min(0).max(1)
+    })).optional(),
     custom_regex: z.string().optional()
   })),
+  secrets: z.array(z.object({
+    type: z.enum([
+      'aws_access_key_id', 'aws_secret_access_key', 'github_token', 'gitlab_token', 'slack_token',
+      'slack_webhook', 'stripe_key', 'google_api_key', 'jwt', 'private_key', 'ssh_private_key',
+      'password', 'generic_secret'
+    ]),
+    pattern: z.string(),
+    position: z.number(),
+    confidence: z.number().min(0).max(1),
+    entropy: z.number().optional()
+  })),
+  composite_findings: z.array(z.object({
+    id: z.string(),
+    rule: z.string(),
+    severity: z.enum(['low', 'medium', 'high', 'critical']),
+    weight: z.number().min(0),
+    finding_ids: z.array(z.string())
+  })).optional(),
+  suppressed_findings: z.array(z.object({
+    detector: z.enum(['banned_phrase', 'pii', 'secret']),
+    type_: z.string(),
+    pattern: z.string(),
+    position: z.number(),
+    confidence: z.number().min(0).max(1),
+    allow_list_index: z.number().int().min(0),
+    note: z.string().optional()
+  })).optional(),
   entropy: z.number(),
   stats: z.object({
     total_chunks: z.number(),
@@ -588,11 +837,34 @@ const WasmAnalysisResultSchema = z.object({
     unique_words: z.number(),
     banned_phrase_count: z.number(),
     pii_pattern_count: z.number(),
+    secret_count: z.number(),
     processing_time_ms: z.number(),
     performance: PerformanceMetricsSchema
   })
 });
 
+/**
+ * Zod schema for FindingSelector
+ */
+const FindingSelectorSchema = z.object({
+  detector: z.enum(['banned_phrase', 'pii']),
+  type: z.string().optional(),
+  category: z.string().optional(),
+  min_confidence: z.number().min(0).max(1).optional(),
+  min_count: z.number().int().min(1).optional()
+});
+
+/**
+ * Zod schema for CompositeCondition
+ */
+const CompositeConditionSchema: z.ZodType<CompositeCondition> = z.lazy(() => z.union([
+  z.object({ finding: FindingSelectorSchema }),
+  z.object({ near: z.object({ terms: z.tuple([FindingSelectorSchema, FindingSelectorSchema]), within: z.number().int().min(0) }) }),
+  z.object({ and: z.array(CompositeConditionSchema).min(1) }),
+  z.object({ or: z.array(CompositeConditionSchema).min(1) }),
+  z.object({ not: CompositeConditionSchema })
+]));
+
 /**
  * Zod schema for StreamingConfig
  */
@@ -601,15 +873,52 @@ const StreamingConfigSchema = z.object({
   entropy_threshold: z.number().min(0).max(8),
   risk_threshold: z.number().min(0).max(1),
   max_words: z.number().min(1).max(1000),
-  banned_phrases: z.array(z.string()),
+  banned_phrases: z.array(z.union([z.string(), z.object({
+    pattern: z.string().min(1),
+    type: z.enum(['literal', 'word', 'regex', 'glob']).optional(),
+    case_sensitive: z.boolean().optional(),
+    whole_word: z.boolean().optional(),
+    severity: z.enum(['low', 'medium', 'high', 'critical']).optional(),
+    category: z.string().min(1).optional(),
+    weight: z.number().min(0).optional()
+  })])),
+  phrase_normalization: z.object({
+    enabled: z.boolean().optional(),
+    compatibility: z.boolean().optional(),
+    confusables: z.boolean().optional(),
+    leetspeak: z.boolean().optional(),
+    separators: z.boolean().optional()
+  }).optional(),
   custom_pii_patterns: z.array(z.object({
-    id: z.string(),
-    name: z.string(),
+    name: z.string().regex(/^[a-z][a-z0-9_]{0,63}$/),
     regex: z.string(),
-    type: z.enum(['phone', 'email', 'ssn', 'credit_card', 'ip_address', 'custom']),
-    confidence: z.number().min(0).max(1),
-    enabled: z.boolean()
+    validator: z.union([z.enum(['luhn', 'mod97']), z.object({ lengths: z.array(z.number().min(1)).min(1) })]).optional(),
+    confidence: z.number().min(0).max(1).optional(),
+    keywords: z.object({
+      supporting: z.array(z.string()).optional(),
+      negating: z.array(z.string()).optional()
+    }).optional(),
+    severity: z.enum(['low', 'medium', 'high', 'critical']).optional(),
+    enabled: z.boolean().optional()
+  })).optional(),
+  composite_rules: z.array(z.object({
+    name: z.string().min(1),
+    condition: CompositeConditionSchema,
+    severity: z.enum(['low', 'medium', 'high', 'critical']).optional(),
+    weight: z.number().min(0).optional()
   })).optional(),
+  allow_list: z.array(z.object({
+    value: z.string().optional(),
+    sha256: z.string().regex(/^[0-9a-fA-F]{64}$/).optional(),
+    regex: z.string().optional(),
+    context: z.string().min(1).max(256).optional(),
+    detector: z.enum(['banned_phrase', 'pii', 'secret']).optional(),
+    type: z.string().optional(),
+    note: z.string().optional()
+  }).refine(
+    entry => [entry.value, entry.sha256, entry.regex, entry.context].filter(v => v !== undefined).length === 1,
+    { message: 'Set exactly one of value, sha256, regex or context' }
+  )).optional(),
   chunk_size: z.number().min(1024).max(10485760).optional() // 1KB to 10MB
 });
 
@@ -818,10 +1127,17 @@ export type {
   WasmAnalysisResult,
   BannedPhraseMatch,
   PIIPattern,
+  SecretFinding,
+  CompositeFinding,
+  SuppressedFinding,
   ProcessingStats,
   PerformanceMetrics,
   StreamingConfig,
   CustomPIIPattern,
+  CompositeRule,
+  CompositeCondition,
+  FindingSelector,
+  AllowListEntry,
   MessageType,
   BaseMessage,
   AnalyzeFileMessage,
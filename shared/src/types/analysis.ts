@@ -1,10 +1,13 @@
+/** Decisions ordered from least to most restrictive */
+export type Decision = 'allow' | 'warn' | 'justify' | 'redact' | 'block';
+
 export interface AnalysisResult {
   topWords: Array<{ word: string; count: number }>;
   bannedPhrases: Array<{ phrase: string; count: number }>;
   piiPatterns: Array<{ pattern: string; count: number }>;
   entropy: number;
   isObfuscated: boolean;
-  decision: 'allow' | 'block';
+  decision: Decision;
   reason: string;
 }
 
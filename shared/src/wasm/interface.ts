@@ -8,10 +8,11 @@ export interface WASMModule {
   getTopWords(text: string, count: number): Promise<string[]>;
   
   // Streaming interface for large files
-  createStreamingAnalyzer(): any;
+  createStreamingAnalyzer(config?: any): any;
   processChunk(analyzer: any, chunk: string): void;
   finalizeStreaming(analyzer: any): AnalysisResult;
   getStreamingStats(analyzer: any): any;
+  freeStreamingAnalyzer(analyzer: any): void;
 }
 
 export interface WASMLoader {
@@ -25,7 +26,7 @@ export interface AnalysisResult {
   piiPatterns: PIIPattern[];
   entropy: number;
   isObfuscated: boolean;
-  decision: 'allow' | 'block';
+  decision: 'allow' | 'warn' | 'justify' | 'redact' | 'block';
   reason: string;
   riskScore: number;
   stats?: {
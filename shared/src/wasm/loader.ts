@@ -100,22 +100,28 @@ export class WASMLoaderImpl implements WASMLoader {
 
       console.log('[WASM] Module initialized successfully');
 
-      // Internal helper to create an analyzer handle that keeps its module instance
-      const createAnalyzerHandle = (config?: any) => {
-        const moduleInstance = new wasmNs.WasmModule();
-        const analyzer = config
-          ? moduleInstance.init_streaming_with_config(config)
-          : moduleInstance.init_streaming();
-        return { __module: moduleInstance, __analyzer: analyzer };
+      // Internal helper to create a streaming session; the analyzer state stays in WASM memory
+      const createSession = (config?: any) => new wasmNs.StreamingSession(config);
+
+      const assertSession = (handle: any) => {
+        if (!handle || typeof handle.push_chunk !== 'function') {
+          throw new Error('Invalid analyzer handle');
+        }
       };
 
       // Return a wrapper that implements our WASMModule interface
       return {
         analyzeFile: async (content: string) => {
-          const handle = createAnalyzerHandle();
-          handle.__module.process_chunk(handle.__analyzer, content);
-          const result = handle.__module.finalize_streaming(handle.__analyzer);
-          const stats = handle.__module.get_streaming_stats(handle.__analyzer);
+          const session = createSession();
+          let result: any;
+          let stats: any;
+          try {
+            session.push_chunk(content);
+            result = session.finalize();
+            stats = session.stats();
+          } finally {
+            session.free();
+          }
 
           return {
             topWords: result.top_words || [],
@@ -156,21 +162,17 @@ export class WASMLoaderImpl implements WASMLoader {
 
         // Streaming interface
         createStreamingAnalyzer: (config?: any) => {
-          return createAnalyzerHandle(config);
+          return createSession(config);
         },
 
         processChunk: (handle: any, chunk: string) => {
-          if (!handle || !handle.__module || !handle.__analyzer) {
-            throw new Error('Invalid analyzer handle');
-          }
-          return handle.__module.process_chunk(handle.__analyzer, chunk);
+          assertSession(handle);
+          handle.push_chunk(chunk);
         },
 
         finalizeStreaming: (handle: any) => {
-          if (!handle || !handle.__module || !handle.__analyzer) {
-            throw new Error('Invalid analyzer handle');
-          }
-          const raw = handle.__module.finalize_streaming(handle.__analyzer);
+          assertSession(handle);
+          const raw = handle.finalize();
           // Normalize field names to camelCase expected by background/content code
           return {
             topWords: raw?.top_words ?? raw?.topWords ?? [],
@@ -185,10 +187,13 @@ export class WASMLoaderImpl implements WASMLoader {
         },
 
         getStreamingStats: (handle: any) => {
-          if (!handle || !handle.__module || !handle.__analyzer) {
-            throw new Error('Invalid analyzer handle');
-          }
-          return handle.__module.get_streaming_stats(handle.__analyzer);
+          assertSession(handle);
+          return handle.stats();
+        },
+
+        freeStreamingAnalyzer: (handle: any) => {
+          assertSession(handle);
+          handle.free();
         }
       };
       
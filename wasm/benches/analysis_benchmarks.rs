@@ -0,0 +1,74 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Bencher, BenchmarkId, Criterion, Throughput};
+use wasm::analysis::{BannedPhraseRule, PhraseScanner};
+use wasm::engine::analyze_text;
+use wasm::streaming::StreamingConfig;
+use wasm::utils::stream::ChunkWindow;
+
+/// Compliance-style dictionary of `size` distinct multi-word terms
+fn dictionary(size: usize) -> Vec<String> {
+    (0..size).map(|i| format!("project {} codename", i)).collect()
+}
+
+/// About `bytes` of prose with a dictionary term every few lines
+fn document(bytes: usize) -> String {
+    let mut text = String::with_capacity(bytes + 100);
+    let mut line = 0;
+    while text.len() < bytes {
+        if line % 8 == 0 {
+            text.push_str(&format!("Status of project {} codename is confidential.\n", line % 1000));
+        } else {
+            text.push_str("The quarterly report covers revenue, hiring and the roadmap.\n");
+        }
+        line += 1;
+    }
+    text
+}
+
+/// Time a full scan of `text`, leaving out building the automaton
+fn bench_scan(b: &mut Bencher, phrases: &[String], text: &str) {
+    let mut window = ChunkWindow::new();
+    window.push(text);
+    b.iter_batched(
+        || PhraseScanner::new(phrases),
+        |mut scanner| scanner.scan(&window, window.end()).len(),
+        BatchSize::LargeInput,
+    )
+}
+
+/// Scan time should stay flat as the dictionary grows
+fn bench_dictionary_size(c: &mut Criterion) {
+    let text = document(100 * 1024);
+    let mut group = c.benchmark_group("banned_phrases/dictionary_size");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    for size in [10, 1_000, 5_000] {
+        let phrases = dictionary(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &phrases, |b, phrases| bench_scan(b, phrases, &text));
+    }
+    group.finish();
+}
+
+/// Scan time should grow linearly with the text
+fn bench_text_size(c: &mut Criterion) {
+    let phrases = dictionary(5_000);
+    let mut group = c.benchmark_group("banned_phrases/text_size");
+    for bytes in [10 * 1024, 100 * 1024, 1024 * 1024] {
+        let text = document(bytes);
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(bytes), &text, |b, text| bench_scan(b, &phrases, text));
+    }
+    group.finish();
+}
+
+/// Full analysis with a large dictionary, one-shot
+fn bench_analyze_text(c: &mut Criterion) {
+    let text = document(100 * 1024);
+    let banned_phrases = dictionary(5_000).iter().map(|phrase| BannedPhraseRule::literal(phrase)).collect();
+    let config = StreamingConfig { banned_phrases, ..StreamingConfig::default() };
+    let mut group = c.benchmark_group("analyze_text");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.bench_function("100KiB", |b| b.iter(|| analyze_text(black_box(&text), config.clone()).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, bench_dictionary_size, bench_text_size, bench_analyze_text);
+criterion_main!(benches);
@@ -0,0 +1,237 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::analysis::custom::compile_bounded_regex;
+use crate::policy::{BANNED_PHRASE_DETECTOR, PII_DETECTOR, SECRET_DETECTOR};
+use crate::utils::hash::sha256_hex;
+use crate::utils::stream::ChunkWindow;
+use crate::utils::text::{ceil_char_boundary, floor_char_boundary};
+
+// Allow-list entries suppress known false positives, such as a public support
+// number, boilerplate footers or test card numbers, before findings are
+// scored. Entries are checked when a finding is settled, while its text and
+// surroundings are still held by the streaming window.
+
+/// Longest context phrase, in bytes
+pub const MAX_CONTEXT_BYTES: usize = 256;
+
+/// Bytes on each side of a finding a context phrase must reach into
+pub const CONTEXT_DISTANCE: usize = 64;
+
+/// Longest whitespace run between the words of a context phrase
+const MAX_CONTEXT_GAP: usize = 16;
+
+/// An entry of `StreamingConfig::allow_list`; exactly one of `value`,
+/// `sha256`, `regex` or `context` is set
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AllowListEntry {
+    /// Value allowed, compared on its letters and digits, ignoring case
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Hex SHA-256 of an allowed value's lowercased letters and digits, for
+    /// values that should not appear in the configuration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Regex the whole matched text must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+    /// Phrase found within `CONTEXT_DISTANCE` bytes of the finding, or
+    /// around it, ignoring case and how its words are spaced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// `banned_phrase`, `pii` or `secret`; any when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detector: Option<String>,
+    /// PII or secret type, or banned phrase; any when omitted
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    /// Why the entry exists, reported with the findings it suppresses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug)]
+enum Matcher {
+    Value(String),
+    Sha256(String),
+    Regex(Regex),
+    /// Regex of the phrase and the longest text it can match
+    Context(Regex, usize),
+}
+
+/// Compiled allow-list
+#[derive(Debug, Default)]
+pub struct AllowList {
+    entries: Vec<(AllowListEntry, Matcher)>,
+    context_radius: usize,
+}
+
+/// Letters and digits of `value`, lowercased
+fn compact(value: &str) -> String {
+    value.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+impl AllowListEntry {
+    fn compile(&self, path: &str) -> Result<Matcher, String> {
+        let set = [self.value.is_some(), self.sha256.is_some(), self.regex.is_some(), self.context.is_some()];
+        if set.iter().filter(|&&s| s).count() != 1 {
+            return Err(format!("{} must set exactly one of value, sha256, regex or context", path));
+        }
+        if let Some(detector) = &self.detector {
+            if ![BANNED_PHRASE_DETECTOR, PII_DETECTOR, SECRET_DETECTOR].contains(&detector.as_str()) {
+                return Err(format!(
+                    "{}.detector must be '{}', '{}' or '{}', got '{}'",
+                    path, BANNED_PHRASE_DETECTOR, PII_DETECTOR, SECRET_DETECTOR, detector
+                ));
+            }
+        }
+
+        if let Some(value) = &self.value {
+            let value = compact(value);
+            if value.is_empty() {
+                return Err(format!("{}.value must contain a letter or digit", path));
+            }
+            return Ok(Matcher::Value(value));
+        }
+        if let Some(digest) = &self.sha256 {
+            if digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("{}.sha256 must be 64 hex digits", path));
+            }
+            return Ok(Matcher::Sha256(digest.to_ascii_lowercase()));
+        }
+        if let Some(regex) = &self.regex {
+            let regex = Regex::new(&format!("^(?:{})$", regex))
+                .map_err(|e| format!("{}.regex is not a valid regex: {}", path, e))?;
+            return Ok(Matcher::Regex(regex));
+        }
+
+        let context = self.context.as_deref().unwrap_or_default();
+        if context.split_whitespace().next().is_none() {
+            return Err(format!("{}.context must not be empty", path));
+        }
+        if context.len() > MAX_CONTEXT_BYTES {
+            return Err(format!("{}.context must be at most {} bytes", path, MAX_CONTEXT_BYTES));
+        }
+        let words: Vec<String> = context.split_whitespace().map(regex::escape).collect();
+        let source = format!(r"(?i:{})", words.join(&format!(r"\s{{1,{}}}", MAX_CONTEXT_GAP)));
+        let (regex, max_match_bytes) = compile_bounded_regex(&source, &format!("{}.context", path), usize::MAX)?;
+        Ok(Matcher::Context(regex, max_match_bytes))
+    }
+
+    fn applies_to(&self, detector: &str, type_: &str) -> bool {
+        self.detector.as_deref().is_none_or(|d| d == detector) && self.type_.as_deref().is_none_or(|t| t == type_)
+    }
+}
+
+impl AllowList {
+    /// Compile the entries, naming the offending field of an invalid one
+    pub fn new(entries: &[AllowListEntry]) -> Result<Self, String> {
+        let mut allow_list = AllowList::default();
+        for (i, entry) in entries.iter().enumerate() {
+            let matcher = entry.compile(&format!("allow_list[{}]", i))?;
+            if let Matcher::Context(_, max_match_bytes) = matcher {
+                allow_list.context_radius = allow_list.context_radius.max(CONTEXT_DISTANCE + max_match_bytes);
+            }
+            allow_list.entries.push((entry.clone(), matcher));
+        }
+        Ok(allow_list)
+    }
+
+    /// Bytes around a finding that must be held to check context phrases
+    pub fn context_radius(&self) -> usize {
+        self.context_radius
+    }
+
+    /// Index and entry of the first entry allowing the finding of `detector`
+    /// and `type_` at the absolute span `start..end` of `window`
+    pub fn allowed_by(&self, window: &ChunkWindow, detector: &str, type_: &str, start: usize, end: usize) -> Option<(usize, &AllowListEntry)> {
+        let value = window.slice(start, end);
+        let mut compacted = None;
+        self.entries
+            .iter()
+            .enumerate()
+            .find(|(_, (entry, matcher))| {
+                entry.applies_to(detector, type_)
+                    && match matcher {
+                        Matcher::Value(allowed) => compacted.get_or_insert_with(|| compact(value)) == allowed,
+                        Matcher::Sha256(digest) => &sha256_hex(compacted.get_or_insert_with(|| compact(value)).as_bytes()) == digest,
+                        Matcher::Regex(regex) => regex.is_match(value),
+                        Matcher::Context(regex, max_match_bytes) => near_context(window, regex, *max_match_bytes, start, end),
+                    }
+            })
+            .map(|(i, (entry, _))| (i, entry))
+    }
+}
+
+/// Whether an occurrence of `regex` overlaps the span widened by `CONTEXT_DISTANCE`
+fn near_context(window: &ChunkWindow, regex: &Regex, max_match_bytes: usize, start: usize, end: usize) -> bool {
+    let text = window.text();
+    let from = window.relative(start.saturating_sub(CONTEXT_DISTANCE));
+    let to = window.relative(end + CONTEXT_DISTANCE).min(text.len());
+    let search = floor_char_boundary(text, from.saturating_sub(max_match_bytes))
+        ..ceil_char_boundary(text, to + max_match_bytes);
+    regex
+        .find_iter(&text[search.clone()])
+        .any(|m| search.start + m.end() > from && search.start + m.start() < to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(json: serde_json::Value) -> AllowListEntry {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn allowed(allow_list: &AllowList, text: &str, detector: &str, type_: &str, value: &str) -> Option<usize> {
+        let mut window = ChunkWindow::new();
+        window.push(text);
+        let start = text.find(value).unwrap();
+        allow_list.allowed_by(&window, detector, type_, start, start + value.len()).map(|(i, _)| i)
+    }
+
+    #[test]
+    fn test_entry_kinds() {
+        let allow_list = AllowList::new(&[
+            entry(serde_json::json!({"value": "123-456-7890", "type": "phone"})),
+            entry(serde_json::json!({"sha256": sha256_hex(b"4111111111111111")})),
+            entry(serde_json::json!({"regex": r"[\w.]+@example\.com", "detector": "pii"})),
+            entry(serde_json::json!({"context": "this email is  confidential", "detector": "banned_phrase"})),
+            entry(serde_json::json!({"context": "test card"})),
+        ])
+        .unwrap();
+
+        assert_eq!(allowed(&allow_list, "call (123) 456 7890", "pii", "phone", "(123) 456 7890"), Some(0));
+        assert_eq!(allowed(&allow_list, "fax 123-456-7890", "pii", "fax", "123-456-7890"), None);
+        assert_eq!(allowed(&allow_list, "card 4111 1111 1111 1111", "pii", "credit_card", "4111 1111 1111 1111"), Some(1));
+        assert_eq!(allowed(&allow_list, "mail jane@example.com", "pii", "email", "jane@example.com"), Some(2));
+        assert_eq!(allowed(&allow_list, "mail jane@example.com.evil", "pii", "email", "jane@example.com.evil"), None);
+
+        let footer = "Regards\n--\nThis email is\nCONFIDENTIAL and intended for its recipient.";
+        assert_eq!(allowed(&allow_list, footer, "banned_phrase", "confidential", "CONFIDENTIAL"), Some(3));
+        assert_eq!(allowed(&allow_list, "This is confidential.", "banned_phrase", "confidential", "confidential"), None);
+
+        let far = format!("Test card below.{}4532 0151 1283 0366", " ".repeat(CONTEXT_DISTANCE));
+        assert_eq!(allowed(&allow_list, "Test card: 4532 0151 1283 0366", "pii", "credit_card", "4532"), Some(4));
+        assert_eq!(allowed(&allow_list, &far, "pii", "credit_card", "4532"), None);
+    }
+
+    #[test]
+    fn test_invalid_entries() {
+        let cases = [
+            (serde_json::json!({"type": "phone"}), "allow_list[0] must set exactly one of value, sha256, regex or context"),
+            (serde_json::json!({"value": "a", "regex": "a"}), "allow_list[0] must set exactly one of value, sha256, regex or context"),
+            (serde_json::json!({"value": "--"}), "allow_list[0].value must contain a letter or digit"),
+            (serde_json::json!({"sha256": "abc"}), "allow_list[0].sha256 must be 64 hex digits"),
+            (serde_json::json!({"context": " "}), "allow_list[0].context must not be empty"),
+            (serde_json::json!({"context": "x ".repeat(200)}), "allow_list[0].context must be at most 256 bytes"),
+            (serde_json::json!({"value": "a", "detector": "entropy"}), "allow_list[0].detector must be 'banned_phrase', 'pii' or 'secret', got 'entropy'"),
+        ];
+        for (json, error) in cases {
+            assert_eq!(AllowList::new(&[entry(json)]).unwrap_err(), error);
+        }
+
+        let err = AllowList::new(&[entry(serde_json::json!({"regex": "("}))]).unwrap_err();
+        assert!(err.starts_with("allow_list[0].regex is not a valid regex: "), "{}", err);
+    }
+}
@@ -0,0 +1,128 @@
+//! Check digit algorithms shared by the PII validators.
+//!
+//! Every function reads ASCII digits only, except `mod_97` which also reads
+//! letters; callers strip separators first.
+
+fn digit_values(digits: &str) -> impl DoubleEndedIterator<Item = u32> + '_ {
+    digits.chars().filter_map(|c| c.to_digit(10))
+}
+
+/// Luhn (mod 10) check, used by payment cards and Canadian SINs
+pub fn luhn_check(digits: &str) -> bool {
+    let mut sum = 0;
+    let mut alternate = false;
+
+    for mut num in digit_values(digits).rev() {
+        if alternate {
+            num *= 2;
+            if num > 9 {
+                num = (num % 10) + 1;
+            }
+        }
+        sum += num;
+        alternate = !alternate;
+    }
+
+    sum % 10 == 0
+}
+
+const VERHOEFF_D: [[u8; 10]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+    [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+    [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+    [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+    [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+    [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+    [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+    [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+    [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+];
+
+const VERHOEFF_P: [[u8; 10]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+    [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+    [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+    [9, 4, 5, 3, 1, 2, 6, 8, 7, 0],
+    [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+    [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+    [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+];
+
+/// Verhoeff check, used by Aadhaar numbers; catches every single-digit error
+/// and adjacent transposition
+pub fn verhoeff_check(digits: &str) -> bool {
+    let check = digit_values(digits)
+        .rev()
+        .enumerate()
+        .fold(0u8, |check, (i, d)| VERHOEFF_D[check as usize][VERHOEFF_P[i % 8][d as usize] as usize]);
+    check == 0
+}
+
+/// ISO 7064 MOD 11,10 check digit for `digits`, used by the German Steuer-ID
+pub fn iso7064_mod_11_10(digits: &str) -> u32 {
+    let product = digit_values(digits).fold(10, |product, d| {
+        let sum = match (d + product) % 10 {
+            0 => 10,
+            sum => sum,
+        };
+        (sum * 2) % 11
+    });
+    (11 - product) % 10
+}
+
+/// Weighted sum mod 11 check digit, as used by the Brazilian CPF and CNPJ:
+/// 0 when the remainder is below 2, otherwise 11 minus the remainder
+pub fn weighted_mod_11(digits: &str, weights: &[u32]) -> u32 {
+    let sum: u32 = digit_values(digits).zip(weights).map(|(d, w)| d * w).sum();
+    match sum % 11 {
+        r if r < 2 => 0,
+        r => 11 - r,
+    }
+}
+
+/// Remainder mod 97 of a number written in digits and letters, letters
+/// counting as 10 (A) to 35 (Z), as in ISO 7064 MOD 97-10 and IBANs
+pub fn mod_97(alphanumeric: &str) -> u32 {
+    alphanumeric
+        .chars()
+        .filter_map(|c| c.to_digit(36))
+        .fold(0, |remainder, value| {
+            let shift = if value < 10 { 10 } else { 100 };
+            (remainder * shift + value) % 97
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_luhn() {
+        assert!(luhn_check("4532015112830366"));
+        assert!(luhn_check("046454286"));
+        assert!(!luhn_check("4532015112830367"));
+    }
+
+    #[test]
+    fn test_verhoeff() {
+        assert!(verhoeff_check("234123412346"));
+        assert!(!verhoeff_check("234123412347"));
+        // Adjacent transposition
+        assert!(!verhoeff_check("234123412364"));
+    }
+
+    #[test]
+    fn test_mod_11() {
+        assert_eq!(iso7064_mod_11_10("8609574271"), 9);
+        assert_eq!(weighted_mod_11("529982247", &[10, 9, 8, 7, 6, 5, 4, 3, 2]), 2);
+    }
+
+    #[test]
+    fn test_mod_97() {
+        assert_eq!(mod_97("370400440532013000DE89"), 1);
+        assert_eq!(mod_97("3214282912345698765432161182"), 1);
+        assert_eq!(mod_97("98"), 1);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::analysis::checksum::{luhn_check, mod_97};
+use crate::analysis::keywords::{is_keyword_type, ContextKeywords};
+use crate::types::Severity;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// User-defined PII detectors, for identifiers only the deploying organization
+// knows about (employee IDs, project codes, customer numbers). They are
+// scanned, scored and resolved against overlaps like the built-in detectors.
+
+/// Longest match a custom regex may produce. Streaming holds back this much
+/// text, so patterns must have a bounded length.
+pub const MAX_CUSTOM_MATCH_BYTES: usize = 1024;
+
+/// A custom PII detector definition from `StreamingConfig::custom_pii_patterns`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomPiiPattern {
+    /// Reported as the finding type: lowercase letters, digits and underscores
+    pub name: String,
+    pub regex: String,
+    /// Check a match must pass to be reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validator: Option<CustomValidator>,
+    #[serde(default = "default_confidence")]
+    pub confidence: f64,
+    /// Supporting and negating keywords, as for the built-in detectors
+    #[serde(default)]
+    pub keywords: ContextKeywords,
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_confidence() -> f64 {
+    0.8
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Check applied to the letters and digits of a match, ignoring separators
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomValidator {
+    /// Luhn (mod 10) check digit over the digits, ignoring any prefix letters
+    Luhn,
+    /// ISO 7064 MOD 97-10: the value, letters counting 10 (A) to 35 (Z), is 1 mod 97
+    Mod97,
+    /// Number of letters and digits is one of these
+    Lengths(Vec<usize>),
+}
+
+impl CustomValidator {
+    pub fn accepts(&self, value: &str) -> bool {
+        let compact: String = value.chars().filter(char::is_ascii_alphanumeric).collect();
+        match self {
+            CustomValidator::Luhn => compact.chars().any(|c| c.is_ascii_digit()) && luhn_check(&compact),
+            CustomValidator::Mod97 => !compact.is_empty() && mod_97(&compact.to_ascii_uppercase()) == 1,
+            CustomValidator::Lengths(lengths) => lengths.contains(&compact.len()),
+        }
+    }
+}
+
+/// Longest match of the regex `pattern` configured at `field`, checking it
+/// parses, cannot match empty text and matches at most `max_bytes`
+pub fn bounded_regex_len(pattern: &str, field: &str, max_bytes: usize) -> Result<usize, String> {
+    let hir = regex_syntax::Parser::new()
+        .parse(pattern)
+        .map_err(|e| format!("{} is not a valid regex: {}", field, e))?;
+    let properties = hir.properties();
+    if properties.minimum_len() == Some(0) {
+        return Err(format!("{} must not match empty text", field));
+    }
+    match properties.maximum_len() {
+        Some(max) if max <= max_bytes => Ok(max),
+        _ => Err(format!(
+            "{} must match at most {} bytes; bound repetitions with {{m,n}} instead of + or *",
+            field, max_bytes
+        )),
+    }
+}
+
+/// Compiled bounded regex and the longest match it can produce
+pub fn compile_bounded_regex(pattern: &str, field: &str, max_bytes: usize) -> Result<(Regex, usize), String> {
+    let max_match_bytes = bounded_regex_len(pattern, field, max_bytes)?;
+    let regex = Regex::new(pattern).map_err(|e| format!("{} is not a valid regex: {}", field, e))?;
+    Ok((regex, max_match_bytes))
+}
+
+impl CustomPiiPattern {
+    /// Compiled regex and the longest match it can produce
+    pub fn compile(&self, path: &str) -> Result<(Regex, usize), String> {
+        compile_bounded_regex(&self.regex, &format!("{}.regex", path), MAX_CUSTOM_MATCH_BYTES)
+    }
+
+    fn validate(&self, path: &str) -> Result<(), String> {
+        let valid_name = self.name.len() <= 64
+            && self.name.starts_with(|c: char| c.is_ascii_lowercase())
+            && self.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid_name {
+            return Err(format!(
+                "{}.name must be lowercase letters, digits and underscores, got '{}'",
+                path, self.name
+            ));
+        }
+        if is_keyword_type(&self.name) {
+            return Err(format!("{}.name '{}' is a built-in PII type", path, self.name));
+        }
+        if !(0.0..=1.0).contains(&self.confidence) {
+            return Err(format!("{}.confidence must be between 0 and 1, got {}", path, self.confidence));
+        }
+        if let Some(CustomValidator::Lengths(lengths)) = &self.validator {
+            if lengths.is_empty() || lengths.contains(&0) {
+                return Err(format!("{}.validator.lengths must list lengths of at least 1", path));
+            }
+        }
+        self.keywords.validate(&format!("{}.keywords", path))?;
+        bounded_regex_len(&self.regex, &format!("{}.regex", path), MAX_CUSTOM_MATCH_BYTES).map(|_| ())
+    }
+}
+
+/// Check every custom detector definition, naming the offending field
+pub fn validate_custom_patterns(patterns: &[CustomPiiPattern]) -> Result<(), String> {
+    for (i, pattern) in patterns.iter().enumerate() {
+        let path = format!("custom_pii_patterns[{}]", i);
+        pattern.validate(&path)?;
+        if let Some(first) = patterns[..i].iter().position(|p| p.name == pattern.name) {
+            return Err(format!(
+                "{}.name '{}' is already used by custom_pii_patterns[{}]",
+                path, pattern.name, first
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(name: &str, regex: &str) -> CustomPiiPattern {
+        serde_json::from_value(serde_json::json!({ "name": name, "regex": regex })).unwrap()
+    }
+
+    #[test]
+    fn test_validators() {
+        assert!(CustomValidator::Luhn.accepts("4532-0151-1283-0366"));
+        assert!(!CustomValidator::Luhn.accepts("4532-0151-1283-0367"));
+        assert!(CustomValidator::Mod97.accepts("3214 2829 1234 5698 7654 3216 1182"));
+        assert!(!CustomValidator::Mod97.accepts("3214 2829 1234 5698 7654 3216 1183"));
+        assert!(CustomValidator::Lengths(vec![6, 8]).accepts("EMP-123"));
+        assert!(!CustomValidator::Lengths(vec![6, 8]).accepts("EMP-1234"));
+    }
+
+    #[test]
+    fn test_definition_defaults() {
+        let json = r#"{"name": "employee_id", "regex": "\\bEMP-\\d{6}\\b", "validator": {"lengths": [9]}}"#;
+        let pattern: CustomPiiPattern = serde_json::from_str(json).unwrap();
+
+        assert_eq!(pattern.confidence, 0.8);
+        assert_eq!(pattern.severity, Severity::Medium);
+        assert!(pattern.enabled);
+        assert_eq!(pattern.validator, Some(CustomValidator::Lengths(vec![9])));
+        // Unicode \d also matches digits of up to 4 bytes
+        assert_eq!(pattern.compile("p").unwrap().1, 4 + 6 * 4);
+    }
+
+    #[test]
+    fn test_invalid_definitions() {
+        let cases = vec![
+            (pattern("Employee ID", r"EMP-\d{6}"), "custom_pii_patterns[0].name must be lowercase letters, digits and underscores, got 'Employee ID'"),
+            (pattern("ssn", r"EMP-\d{6}"), "custom_pii_patterns[0].name 'ssn' is a built-in PII type"),
+            (pattern("employee_id", r"\d{0,6}"), "custom_pii_patterns[0].regex must not match empty text"),
+            (
+                pattern("employee_id", r"EMP-\d+"),
+                "custom_pii_patterns[0].regex must match at most 1024 bytes; bound repetitions with {m,n} instead of + or *",
+            ),
+            (CustomPiiPattern { confidence: 1.5, ..pattern("employee_id", r"EMP-\d{6}") }, "custom_pii_patterns[0].confidence must be between 0 and 1, got 1.5"),
+        ];
+        for (pattern, error) in cases {
+            assert_eq!(validate_custom_patterns(&[pattern]).unwrap_err(), error);
+        }
+
+        let err = validate_custom_patterns(&[pattern("employee_id", r"EMP-(\d{6}")]).unwrap_err();
+        assert!(err.starts_with("custom_pii_patterns[0].regex is not a valid regex: "), "{}", err);
+
+        let duplicate = [pattern("employee_id", r"EMP-\d{6}"), pattern("employee_id", r"E-\d{6}")];
+        assert_eq!(
+            validate_custom_patterns(&duplicate).unwrap_err(),
+            "custom_pii_patterns[1].name 'employee_id' is already used by custom_pii_patterns[0]"
+        );
+    }
+}
@@ -1,39 +1,91 @@
 use std::collections::HashMap;
 
-pub fn calculate_shannon_entropy(text: &str) -> f64 {
-    if text.is_empty() {
-        return 0.0;
+/// Running character histogram used to compute Shannon entropy incrementally.
+///
+/// Only lowercase alphanumeric characters are counted, so memory is bounded by
+/// the alphabet in use rather than by the amount of text fed in.
+#[derive(Debug, Clone)]
+pub struct CharHistogram {
+    ascii_counts: [u64; 128],
+    counts: HashMap<char, u64>,
+    total: u64,
+}
+
+impl Default for CharHistogram {
+    fn default() -> Self {
+        Self {
+            ascii_counts: [0; 128],
+            counts: HashMap::new(),
+            total: 0,
+        }
     }
+}
 
-    // Normalize text: lowercase and remove whitespace/punctuation
-    let normalized: String = text
-        .to_lowercase()
-        .chars()
-        .filter(|c| c.is_alphanumeric())
-        .collect();
+impl CharHistogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add all characters of `text` to the histogram
+    pub fn add_text(&mut self, text: &str) {
+        // Normalize text: lowercase and remove whitespace/punctuation
+        for c in text.chars() {
+            if c.is_ascii() {
+                if c.is_ascii_alphanumeric() {
+                    self.ascii_counts[c.to_ascii_lowercase() as usize] += 1;
+                    self.total += 1;
+                }
+                continue;
+            }
+            for lower in c.to_lowercase() {
+                if lower.is_alphanumeric() {
+                    if lower.is_ascii() {
+                        self.ascii_counts[lower as usize] += 1;
+                    } else {
+                        *self.counts.entry(lower).or_insert(0) += 1;
+                    }
+                    self.total += 1;
+                }
+            }
+        }
+    }
 
-    if normalized.is_empty() {
-        return 0.0;
+    /// Number of characters counted so far
+    pub fn total(&self) -> u64 {
+        self.total
     }
 
-    // Calculate character frequencies
-    let mut char_counts: HashMap<char, usize> = HashMap::new();
-    for c in normalized.chars() {
-        *char_counts.entry(c).or_insert(0) += 1;
+    /// Approximate heap footprint in bytes
+    pub fn memory_footprint(&self) -> usize {
+        std::mem::size_of_val(&self.ascii_counts)
+            + self.counts.capacity() * (std::mem::size_of::<char>() + std::mem::size_of::<u64>())
     }
 
-    let text_len = normalized.len() as f64;
-    let mut entropy = 0.0;
+    /// Shannon entropy of the characters counted so far
+    pub fn entropy(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+
+        let total = self.total as f64;
+        let mut entropy = 0.0;
 
-    // Calculate Shannon entropy: -∑(p_i * log₂(p_i))
-    for count in char_counts.values() {
-        let probability = *count as f64 / text_len;
-        if probability > 0.0 {
-            entropy -= probability * probability.log2();
+        // Calculate Shannon entropy: -∑(p_i * log₂(p_i))
+        for count in self.ascii_counts.iter().chain(self.counts.values()) {
+            let probability = *count as f64 / total;
+            if probability > 0.0 {
+                entropy -= probability * probability.log2();
+            }
         }
+
+        entropy
     }
+}
 
-    entropy
+pub fn calculate_shannon_entropy(text: &str) -> f64 {
+    let mut histogram = CharHistogram::new();
+    histogram.add_text(text);
+    histogram.entropy()
 }
 
 #[cfg(test)]
@@ -380,6 +432,41 @@ mod tests {
         assert!((entropy1 - entropy2).abs() < 0.000001);
     }
 
+    #[test]
+    fn test_incremental_histogram_matches_one_shot() {
+        let text = "The quick brown fox jumps over the lazy dog. Café 123!";
+        let mut histogram = CharHistogram::new();
+        for piece in ["The quick brown ", "fox jumps over", " the lazy dog. ", "Café 123!"] {
+            histogram.add_text(piece);
+        }
+
+        assert!((histogram.entropy() - calculate_shannon_entropy(text)).abs() < 1e-12);
+        assert_eq!(histogram.total(), 42);
+    }
+
+    #[test]
+    fn test_ascii_fast_path_matches_plain_histogram() {
+        // 'İ' lowercases to an ASCII 'i' plus a combining mark, 'ß' and 'Σ'
+        // stay non-ASCII, and 'K' (Kelvin sign) lowercases to ASCII 'k'
+        let text = "İstanbul Straße ΣΊΣΥΦΟΣ \u{212a}elvin naïve café 42 😀 ABC abc";
+
+        let mut counts: HashMap<char, u64> = HashMap::new();
+        for c in text.chars().flat_map(char::to_lowercase).filter(|c| c.is_alphanumeric()) {
+            *counts.entry(c).or_insert(0) += 1;
+        }
+        let total: u64 = counts.values().sum();
+        let expected: f64 = counts
+            .values()
+            .map(|&count| count as f64 / total as f64)
+            .map(|p| -p * p.log2())
+            .sum();
+
+        let mut histogram = CharHistogram::new();
+        histogram.add_text(text);
+        assert_eq!(histogram.total(), total);
+        assert!((histogram.entropy() - expected).abs() < 1e-12);
+    }
+
     #[test]
     fn test_special_characters_ignored() {
         let text1 = "hello world";
@@ -0,0 +1,85 @@
+use crate::analysis::checksum::mod_97;
+
+// Confidence functions for bank identifiers. Each gets the matched text and,
+// where it matters, whether a supporting keyword is nearby (see `keywords`),
+// and returns `None` when the match cannot be the identifier.
+
+/// IBAN length for each country using them
+const IBAN_LENGTHS: &[(&str, usize)] = &[
+    ("AD", 24), ("AE", 23), ("AL", 28), ("AT", 20), ("AZ", 28), ("BA", 20), ("BE", 16), ("BG", 22),
+    ("BH", 22), ("BR", 29), ("BY", 28), ("CH", 21), ("CR", 22), ("CY", 28), ("CZ", 24), ("DE", 22),
+    ("DK", 18), ("DO", 28), ("EE", 20), ("EG", 29), ("ES", 24), ("FI", 18), ("FO", 18), ("FR", 27),
+    ("GB", 22), ("GE", 22), ("GI", 23), ("GL", 18), ("GR", 27), ("GT", 28), ("HR", 21), ("HU", 28),
+    ("IE", 22), ("IL", 23), ("IQ", 23), ("IS", 26), ("IT", 27), ("JO", 30), ("KW", 30), ("KZ", 20),
+    ("LB", 28), ("LC", 32), ("LI", 21), ("LT", 20), ("LU", 20), ("LV", 21), ("MC", 27), ("MD", 24),
+    ("ME", 22), ("MK", 19), ("MR", 27), ("MT", 31), ("MU", 30), ("NL", 18), ("NO", 15), ("PK", 24),
+    ("PL", 28), ("PS", 29), ("PT", 25), ("QA", 29), ("RO", 24), ("RS", 22), ("SA", 24), ("SC", 31),
+    ("SE", 24), ("SI", 19), ("SK", 24), ("SM", 27), ("ST", 25), ("SV", 28), ("TL", 23), ("TN", 24),
+    ("TR", 26), ("UA", 29), ("VA", 22), ("VG", 24), ("XK", 20),
+];
+
+/// ISO 3166-1 alpha-2 codes, for the country part of a BIC
+const COUNTRY_CODES: &str = "AD AE AF AG AI AL AM AO AQ AR AS AT AU AW AX AZ BA BB BD BE BF BG BH BI BJ BL BM BN BO BQ \
+    BR BS BT BV BW BY BZ CA CC CD CF CG CH CI CK CL CM CN CO CR CU CV CW CX CY CZ DE DJ DK DM DO DZ EC EE EG EH ER ES ET \
+    FI FJ FK FM FO FR GA GB GD GE GF GG GH GI GL GM GN GP GQ GR GS GT GU GW GY HK HM HN HR HT HU ID IE IL IM IN IO IQ IR \
+    IS IT JE JM JO JP KE KG KH KI KM KN KP KR KW KY KZ LA LB LC LI LK LR LS LT LU LV LY MA MC MD ME MF MG MH MK ML MM MN \
+    MO MP MQ MR MS MT MU MV MW MX MY MZ NA NC NE NF NG NI NL NO NP NR NU NZ OM PA PE PF PG PH PK PL PM PN PR PS PT PW PY \
+    QA RE RO RS RU RW SA SB SC SD SE SG SH SI SJ SK SL SM SN SO SR SS ST SV SX SY SZ TC TD TF TG TH TJ TK TL TM TN TO TR \
+    TT TV TW TZ UA UG UM US UY UZ VA VC VE VG VI VN VU WF WS XK YE YT ZA ZM ZW";
+
+/// IBAN: a country with IBANs, that country's length, and mod 97 of the
+/// rearranged number equal to 1
+pub fn iban_confidence(iban: &str) -> Option<f64> {
+    let compact: String = iban.chars().filter(|c| !c.is_whitespace()).collect();
+    let (country, _) = compact.split_at(2);
+    let (_, length) = IBAN_LENGTHS.iter().find(|(code, _)| *code == country)?;
+    if compact.len() != *length {
+        return None;
+    }
+
+    let (head, rest) = compact.split_at(4);
+    (mod_97(&format!("{}{}", rest, head)) == 1).then_some(0.95)
+}
+
+/// SWIFT/BIC: bank code, ISO country, location and optional branch. All-letter
+/// codes look like ordinary capitalized words, so they need a keyword nearby;
+/// codes with a digit or the `XXX` head-office branch do not.
+pub fn swift_bic_confidence(bic: &str, keyword: bool) -> Option<f64> {
+    let country = &bic[4..6];
+    if !COUNTRY_CODES.split(' ').any(|code| code == country) {
+        return None;
+    }
+
+    if keyword {
+        Some(0.9)
+    } else if bic.chars().any(|c| c.is_ascii_digit()) || bic.ends_with("XXX") {
+        Some(0.6)
+    } else {
+        None
+    }
+}
+
+/// Whether the first two digits are an assigned ABA prefix: Federal Reserve
+/// districts 01-12, thrifts 21-32, electronic 61-72 and traveler's checks 80
+fn is_aba_prefix(prefix: u32) -> bool {
+    matches!(prefix, 0..=12 | 21..=32 | 61..=72 | 80)
+}
+
+/// US ABA routing number: an assigned prefix and the 3-7-1 weighted checksum.
+/// Confidence rises with a routing keyword nearby.
+pub fn aba_routing_confidence(number: &str, keyword: bool) -> Option<f64> {
+    let digits: Vec<u32> = number.chars().filter_map(|c| c.to_digit(10)).collect();
+    let checksum: u32 = digits.iter().zip([3, 7, 1].iter().cycle()).map(|(d, w)| d * w).sum();
+    let zero = digits.iter().all(|&d| d == 0);
+    if zero || !is_aba_prefix(digits[0] * 10 + digits[1]) || !checksum.is_multiple_of(10) {
+        return None;
+    }
+
+    Some(if keyword { 0.95 } else { 0.6 })
+}
+
+/// Bank account number: 6 to 17 digits have no structure to check, so they
+/// are only reported next to an account keyword
+pub fn bank_account_confidence(_number: &str, keyword: bool) -> Option<f64> {
+    keyword.then_some(0.7)
+}
@@ -1,25 +1,15 @@
 use std::collections::HashMap;
 
-pub fn analyze_word_frequency(text: &str, max_words: usize) -> Vec<(String, usize)> {
-    // Normalize text: lowercase and split into words
-    let text_lower = text.to_lowercase();
-    let words: Vec<&str> = text_lower
-        .split_whitespace()
-        .filter(|word| !word.is_empty())
-        .collect();
+/// Longest word kept by `WordTokenizer`; longer runs are truncated
+pub const MAX_WORD_BYTES: usize = 256;
 
+pub fn analyze_word_frequency(text: &str, max_words: usize) -> Vec<(String, usize)> {
     // Count word frequencies
     let mut word_counts: HashMap<String, usize> = HashMap::new();
-    for word in words {
-        // Clean word: keep only alphanumeric characters
-        let clean_word: String = word.chars()
-            .filter(|c| c.is_alphanumeric())
-            .collect();
-        
-        if !clean_word.is_empty() {
-            *word_counts.entry(clean_word).or_insert(0) += 1;
-        }
-    }
+    let mut tokenizer = WordTokenizer::new();
+    let mut count = |word: &str| *word_counts.entry(word.to_string()).or_insert(0) += 1;
+    tokenizer.feed(text, &mut count);
+    tokenizer.finish(&mut count);
 
     // Sort by frequency (descending) and then alphabetically
     let mut sorted_words: Vec<(String, usize)> = word_counts.into_iter().collect();
@@ -32,6 +22,138 @@ pub fn analyze_word_frequency(text: &str, max_words: usize) -> Vec<(String, usiz
     sorted_words.into_iter().take(max_words).collect()
 }
 
+/// Incremental word splitter.
+///
+/// Words are whitespace-separated, lowercased and reduced to their
+/// alphanumeric characters. A word cut by a chunk boundary is held until the
+/// next chunk (or `finish`), so chunked input yields the same words as the
+/// whole text.
+#[derive(Debug, Clone, Default)]
+pub struct WordTokenizer {
+    partial: String,
+}
+
+impl WordTokenizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Split `text`, calling `emit` for every word completed within it
+    pub fn feed(&mut self, text: &str, emit: &mut impl FnMut(&str)) {
+        for c in text.chars() {
+            if c.is_whitespace() {
+                self.flush(emit);
+            } else if c.is_ascii() {
+                if c.is_ascii_alphanumeric() {
+                    self.push(c.to_ascii_lowercase());
+                }
+            } else {
+                for lower in c.to_lowercase() {
+                    if lower.is_alphanumeric() {
+                        self.push(lower);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Emit the word held at the end of the input, if any
+    pub fn finish(&mut self, emit: &mut impl FnMut(&str)) {
+        self.flush(emit);
+    }
+
+    /// Approximate heap footprint in bytes
+    pub fn memory_footprint(&self) -> usize {
+        self.partial.capacity()
+    }
+
+    fn push(&mut self, c: char) {
+        if self.partial.len() + c.len_utf8() <= MAX_WORD_BYTES {
+            self.partial.push(c);
+        }
+    }
+
+    fn flush(&mut self, emit: &mut impl FnMut(&str)) {
+        if !self.partial.is_empty() {
+            emit(&self.partial);
+            self.partial.clear();
+        }
+    }
+}
+
+/// Fixed-size word frequency summary.
+///
+/// Counts are exact while fewer than twice `capacity` distinct words have been
+/// seen. Past that point the summary is pruned back to its `capacity` most
+/// frequent words, so heavy hitters are kept while memory stays bounded; a
+/// pruned word that shows up again restarts counting from zero.
+#[derive(Debug, Clone)]
+pub struct TopWordsSketch {
+    capacity: usize,
+    counts: HashMap<String, usize>,
+    word_bytes: usize,
+}
+
+impl TopWordsSketch {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            counts: HashMap::new(),
+            word_bytes: 0,
+        }
+    }
+
+    /// Record one occurrence of `word`
+    pub fn add(&mut self, word: &str) {
+        if let Some(count) = self.counts.get_mut(word) {
+            *count += 1;
+            return;
+        }
+
+        if self.counts.len() >= self.capacity * 2 {
+            self.prune();
+        }
+
+        self.word_bytes += word.len();
+        self.counts.insert(word.to_string(), 1);
+    }
+
+    /// Number of distinct words currently tracked
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Approximate heap footprint in bytes
+    pub fn memory_footprint(&self) -> usize {
+        let entry_overhead = std::mem::size_of::<String>() + std::mem::size_of::<usize>();
+        self.counts.len() * entry_overhead + self.word_bytes
+    }
+
+    /// Top `max_words` words sorted by frequency (descending) and then alphabetically
+    pub fn top(&self, max_words: usize) -> Vec<(String, usize)> {
+        let mut sorted_words: Vec<(String, usize)> = self.counts
+            .iter()
+            .map(|(word, count)| (word.clone(), *count))
+            .collect();
+        sorted_words.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        sorted_words.truncate(max_words);
+        sorted_words
+    }
+
+    fn prune(&mut self) {
+        let mut entries: Vec<(String, usize)> = self.counts.drain().collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        entries.truncate(self.capacity);
+
+        self.word_bytes = entries.iter().map(|(word, _)| word.len()).sum();
+        self.counts.extend(entries);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,6 +326,31 @@ mod tests {
         assert_eq!(result.len(), 0);
     }
 
+    #[test]
+    fn test_sketch_exact_below_capacity() {
+        let mut sketch = TopWordsSketch::new(10);
+        for word in "hello world hello test world hello".split_whitespace() {
+            sketch.add(word);
+        }
+
+        assert_eq!(sketch.len(), 3);
+        assert_eq!(sketch.top(2), vec![("hello".to_string(), 3), ("world".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_sketch_bounded_and_keeps_heavy_hitters() {
+        let mut sketch = TopWordsSketch::new(4);
+        for i in 0..1000 {
+            sketch.add("frequent");
+            sketch.add(&format!("rare{}", i));
+        }
+
+        assert!(sketch.len() <= 8);
+        let top = sketch.top(1);
+        assert_eq!(top[0].0, "frequent");
+        assert_eq!(top[0].1, 1000);
+    }
+
     #[test]
     fn test_mixed_case_and_punctuation() {
         let text = "Hello, World! HELLO world...";
@@ -213,4 +360,30 @@ mod tests {
         assert_eq!(result[0], ("hello".to_string(), 2));
         assert_eq!(result[1], ("world".to_string(), 2));
     }
+
+    #[test]
+    fn test_tokenizer_joins_words_split_across_chunks() {
+        let mut tokenizer = WordTokenizer::new();
+        let mut words = Vec::new();
+        let mut collect = |word: &str| words.push(word.to_string());
+
+        tokenizer.feed("Hello wo", &mut collect);
+        tokenizer.feed("RLD, it's", &mut collect);
+        tokenizer.finish(&mut collect);
+
+        assert_eq!(words, vec!["hello", "world", "its"]);
+    }
+
+    #[test]
+    fn test_tokenizer_truncates_long_words() {
+        let mut tokenizer = WordTokenizer::new();
+        let mut words = Vec::new();
+        let long = "x".repeat(MAX_WORD_BYTES * 2);
+
+        tokenizer.feed(&long, &mut |word: &str| words.push(word.to_string()));
+        tokenizer.finish(&mut |word: &str| words.push(word.to_string()));
+
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].len(), MAX_WORD_BYTES);
+    }
 }
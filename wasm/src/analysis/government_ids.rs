@@ -0,0 +1,203 @@
+use crate::analysis::checksum::{iso7064_mod_11_10, luhn_check, verhoeff_check, weighted_mod_11};
+
+// Confidence functions for national ID numbers. Each returns `None` when the
+// match cannot be the ID. IDs with a check digit score higher when it verifies;
+// a failed check is only reported for formats distinctive enough to be the ID
+// anyway, mistyped.
+
+fn digits(id: &str) -> String {
+    id.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+/// Whether `id` contains anything besides the characters of the number itself
+fn is_formatted(id: &str) -> bool {
+    id.chars().any(|c| !c.is_ascii_alphanumeric())
+}
+
+/// Confidence of a number that passed its check. A checksum passes for one
+/// in ten or eleven random numbers, so bare digits, as common as order or
+/// invoice numbers, need a supporting keyword, which also raises grouped ones
+fn keyword_gated(id: &str, keyword: bool, confidence: f64) -> Option<f64> {
+    match (is_formatted(id), keyword) {
+        (true, true) => Some(confidence + 0.1),
+        (false, false) => None,
+        _ => Some(confidence),
+    }
+}
+
+/// UK National Insurance number: two prefix letters from the allowed set,
+/// six digits and a suffix A-D. There is no check digit.
+pub fn uk_nino_confidence(id: &str) -> Option<f64> {
+    let letters: Vec<char> = id.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+    let (first, second) = (letters[0], letters[1]);
+    let prefix: String = letters[..2].iter().collect();
+
+    let valid = !"DFIQUV".contains(first)
+        && !"DFIOQUV".contains(second)
+        && !["BG", "GB", "KN", "NK", "NT", "TN", "ZZ"].contains(&prefix.as_str());
+    valid.then_some(0.85)
+}
+
+/// Canadian Social Insurance Number: nine digits, Luhn checked. Numbers
+/// starting with 0 or 8 are never issued.
+pub fn ca_sin_confidence(id: &str, keyword: bool) -> Option<f64> {
+    let digits = digits(id);
+    let valid = !digits.starts_with(['0', '8']) && luhn_check(&digits);
+    valid.then(|| keyword_gated(id, keyword, 0.85)).flatten()
+}
+
+/// French INSEE (NIR) number: 13 digits and a key of 97 minus the number
+/// mod 97. Corsican departments 2A and 2B count as 19 and 18.
+pub fn fr_insee_confidence(id: &str) -> Option<f64> {
+    let compact: String = id.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    let (number, key) = compact.split_at(13);
+    let number = number.replace("2A", "19").replace("2B", "18");
+
+    let valid = match (number.parse::<u64>(), key.parse::<u64>()) {
+        (Ok(number), Ok(key)) => 97 - number % 97 == key,
+        _ => false,
+    };
+    if valid {
+        Some(0.9)
+    } else {
+        // The 1-2-2-2-3-3-2 grouping is specific to INSEE numbers
+        is_formatted(id).then_some(0.4)
+    }
+}
+
+/// German tax ID (Steuerliche Identifikationsnummer): eleven digits ending in
+/// an ISO 7064 check digit. Among the first ten, exactly one digit repeats,
+/// two or three times.
+pub fn de_steuer_id_confidence(id: &str, keyword: bool) -> Option<f64> {
+    let digits = digits(id);
+    let (body, check) = digits.split_at(10);
+
+    let mut counts = [0; 10];
+    for d in body.chars().filter_map(|c| c.to_digit(10)) {
+        counts[d as usize] += 1;
+    }
+    let repeated = counts.iter().filter(|&&n| n > 1).count();
+    let distribution_ok = repeated == 1 && counts.iter().all(|&n| n <= 3);
+
+    let valid = distribution_ok && check.parse::<u32>() == Ok(iso7064_mod_11_10(body));
+    valid.then(|| keyword_gated(id, keyword, 0.85)).flatten()
+}
+
+/// Values of the odd (1st, 3rd, ...) characters of a Codice Fiscale, by digit
+/// or letter index
+const CODICE_FISCALE_ODD: [u32; 26] = [
+    1, 0, 5, 7, 9, 13, 15, 17, 19, 21, 2, 4, 18, 20, 11, 3, 6, 8, 12, 14, 16, 10, 22, 25, 24, 23,
+];
+
+/// Italian Codice Fiscale: 15 characters encoding name, birth date and place,
+/// followed by a check letter
+pub fn it_codice_fiscale_confidence(id: &str) -> Option<f64> {
+    let chars: Vec<char> = id.chars().collect();
+    let sum: u32 = chars[..15]
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            let index = c.to_digit(10).unwrap_or_else(|| c as u32 - 'A' as u32);
+            if i % 2 == 0 { CODICE_FISCALE_ODD[index as usize] } else { index }
+        })
+        .sum();
+
+    let check = char::from(b'A' + (sum % 26) as u8);
+    // The layout is distinctive enough to report a mistyped check letter
+    Some(if chars[15] == check { 0.95 } else { 0.5 })
+}
+
+const DNI_LETTERS: &[u8; 23] = b"TRWAGMYFPDXBNJZSQVHLCKE";
+
+/// Check letter for a DNI number
+fn dni_letter(number: u32) -> char {
+    DNI_LETTERS[(number % 23) as usize] as char
+}
+
+/// Spanish DNI: eight digits and a check letter
+pub fn es_dni_confidence(id: &str) -> Option<f64> {
+    let number: u32 = digits(id).parse().ok()?;
+    let letter = id.chars().last()?;
+    Some(if letter == dni_letter(number) { 0.9 } else { 0.4 })
+}
+
+/// Spanish NIE (foreigner ID): X, Y or Z standing for 0, 1 or 2, seven digits
+/// and a DNI check letter
+pub fn es_nie_confidence(id: &str) -> Option<f64> {
+    let prefix = match id.chars().next()? {
+        'X' => 0,
+        'Y' => 1,
+        _ => 2,
+    };
+    let number = prefix * 10_000_000 + digits(id).parse::<u32>().ok()?;
+    let letter = id.chars().last()?;
+    Some(if letter == dni_letter(number) { 0.9 } else { 0.4 })
+}
+
+/// Dutch BSN: nine digits passing the 11-proof, where the weighted sum with
+/// weights 9..2 and -1 for the last digit is divisible by 11
+pub fn nl_bsn_confidence(id: &str, keyword: bool) -> Option<f64> {
+    let digits = digits(id);
+    let sum: i32 = digits
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .zip([9, 8, 7, 6, 5, 4, 3, 2, -1])
+        .map(|(d, w)| d as i32 * w)
+        .sum();
+    (sum != 0 && sum % 11 == 0).then(|| keyword_gated(id, keyword, 0.8)).flatten()
+}
+
+/// Indian Aadhaar number: twelve digits, not starting with 0 or 1, ending in a
+/// Verhoeff check digit
+pub fn in_aadhaar_confidence(id: &str) -> Option<f64> {
+    verhoeff_check(&digits(id)).then_some(0.9)
+}
+
+/// Indian PAN: five letters, four digits and a letter. The fourth letter
+/// gives the holder type and must be one of the issued ones.
+pub fn in_pan_confidence(id: &str) -> Option<f64> {
+    let holder = id.chars().nth(3)?;
+    "ABCFGHJLPT".contains(holder).then_some(0.8)
+}
+
+/// A run of one digit, which passes the Brazilian checksums but is never issued
+fn is_repeated_digit(digits: &str) -> bool {
+    digits.chars().all(|c| digits.starts_with(c))
+}
+
+/// Brazilian CPF: nine digits and two mod 11 check digits
+pub fn br_cpf_confidence(id: &str) -> Option<f64> {
+    let digits = digits(id);
+    if is_repeated_digit(&digits) {
+        return None;
+    }
+
+    let first = weighted_mod_11(&digits[..9], &[10, 9, 8, 7, 6, 5, 4, 3, 2]);
+    let second = weighted_mod_11(&digits[..10], &[11, 10, 9, 8, 7, 6, 5, 4, 3, 2]);
+    let valid = digits[9..] == format!("{}{}", first, second);
+    if valid {
+        Some(0.9)
+    } else {
+        // ddd.ddd.ddd-dd is specific to CPF numbers
+        is_formatted(id).then_some(0.5)
+    }
+}
+
+/// Brazilian CNPJ (company ID): twelve digits and two mod 11 check digits
+pub fn br_cnpj_confidence(id: &str) -> Option<f64> {
+    let digits = digits(id);
+    if is_repeated_digit(&digits) {
+        return None;
+    }
+
+    let weights = [6, 5, 4, 3, 2, 9, 8, 7, 6, 5, 4, 3, 2];
+    let first = weighted_mod_11(&digits[..12], &weights[1..]);
+    let second = weighted_mod_11(&digits[..13], &weights);
+    let valid = digits[12..] == format!("{}{}", first, second);
+    if valid {
+        Some(0.9)
+    } else {
+        // dd.ddd.ddd/dddd-dd is specific to CNPJ numbers
+        is_formatted(id).then_some(0.5)
+    }
+}
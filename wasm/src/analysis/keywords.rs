@@ -0,0 +1,194 @@
+use regex::Regex;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Context keywords near a PII match. Supporting keywords ("SSN", "phone")
+// make a match more likely to be the PII it looks like; negating ones
+// ("order #", "invoice") mark numbers that only share its shape.
+
+/// Built-in supporting and negating keywords for each PII type
+pub const DEFAULT_KEYWORDS: &[(&str, &[&str], &[&str])] = &[
+    ("phone", &["phone", "tel", "telephone", "mobile", "cell", "fax", "call", "contact"], NUMBER_NEGATING),
+    ("ssn", &["ssn", "ss#", "social security", "soc sec", "soc. sec", "dob", "date of birth"], NUMBER_NEGATING),
+    ("credit_card", &["card", "credit", "debit", "cc", "cvv", "cvc", "expiry", "exp"], NUMBER_NEGATING),
+    ("ip_address", &["ip", "ipv4", "ipv6", "host", "server", "address"], &["version", "ver", "chapter", "section"]),
+    ("mac_address", &["mac", "ethernet", "hwaddr", "wifi", "bssid"], &[]),
+    ("hostname", &["host", "hostname", "server", "ssh", "url"], &[]),
+    ("email", &["email", "e-mail", "mail", "contact"], &[]),
+    ("uk_nino", &["national insurance", "nino", "ni number"], &[]),
+    ("ca_sin", &["sin", "social insurance"], NUMBER_NEGATING),
+    ("fr_insee", &["insee", "nir", "securite sociale", "sécurité sociale"], NUMBER_NEGATING),
+    ("de_steuer_id", &["steuer-id", "steuer id", "steueridentifikationsnummer", "idnr", "tax id"], NUMBER_NEGATING),
+    ("it_codice_fiscale", &["codice fiscale", "cf"], &[]),
+    ("es_dni", &["dni"], &[]),
+    ("es_nie", &["nie"], &[]),
+    ("nl_bsn", &["bsn", "burgerservicenummer", "sofinummer"], NUMBER_NEGATING),
+    ("in_aadhaar", &["aadhaar", "aadhar", "uidai"], NUMBER_NEGATING),
+    ("in_pan", &["pan", "permanent account"], &[]),
+    ("br_cpf", &["cpf"], NUMBER_NEGATING),
+    ("br_cnpj", &["cnpj"], NUMBER_NEGATING),
+    ("iban", &["iban"], &[]),
+    ("swift_bic", &["swift", "bic", "bank", "banking"], &[]),
+    ("aba_routing", &["routing", "aba", "rtn", "transit"], NUMBER_NEGATING),
+    ("bank_account", &["account", "accounts", "acct", "a/c"], NUMBER_NEGATING),
+];
+
+/// Labels of numbers that are not personal data
+const NUMBER_NEGATING: &[&str] = &[
+    "order", "order #", "invoice", "tracking", "ref", "reference", "serial", "sku", "part number", "transaction",
+    "confirmation", "ticket", "isbn",
+];
+
+lazy_static! {
+    // Compiled once; scanners without configured keywords share these
+    static ref DEFAULT_MATCHERS: HashMap<&'static str, KeywordMatcher> = DEFAULT_KEYWORDS
+        .iter()
+        .map(|&(type_, supporting, negating)| {
+            let matcher = KeywordMatcher {
+                supporting: keyword_regex(supporting.iter().copied()),
+                negating: keyword_regex(negating.iter().copied()),
+            };
+            (type_, matcher)
+        })
+        .collect();
+}
+
+/// Keywords added to the built-in ones for a PII type
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContextKeywords {
+    pub supporting: Vec<String>,
+    pub negating: Vec<String>,
+}
+
+impl ContextKeywords {
+    /// Check no keyword is blank, naming the offending entry under `path`
+    pub fn validate(&self, path: &str) -> Result<(), String> {
+        for (kind, list) in [("supporting", &self.supporting), ("negating", &self.negating)] {
+            if let Some(i) = list.iter().position(|keyword| keyword.trim().is_empty()) {
+                return Err(format!("{}.{}[{}] must not be empty", path, kind, i));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Keywords found near a match, as configured (lowercase)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeywordMatches {
+    pub supporting: Vec<String>,
+    pub negating: Vec<String>,
+}
+
+impl KeywordMatches {
+    /// Whether a supporting keyword was found
+    pub fn supported(&self) -> bool {
+        !self.supporting.is_empty()
+    }
+}
+
+/// Compiled supporting and negating keywords of one PII type
+#[derive(Debug, Clone, Default)]
+pub struct KeywordMatcher {
+    supporting: Option<Regex>,
+    negating: Option<Regex>,
+}
+
+impl KeywordMatcher {
+    /// Matcher for the built-in keywords of `type_` plus `extra`
+    pub fn new(type_: &str, extra: Option<&ContextKeywords>) -> Self {
+        let Some(extra) = extra else {
+            return DEFAULT_MATCHERS.get(type_).cloned().unwrap_or_default();
+        };
+        let (supporting, negating) = DEFAULT_KEYWORDS
+            .iter()
+            .find(|(name, _, _)| *name == type_)
+            .map_or((&[][..], &[][..]), |&(_, supporting, negating)| (supporting, negating));
+        Self {
+            supporting: keyword_regex(supporting.iter().copied().chain(extra.supporting.iter().map(String::as_str))),
+            negating: keyword_regex(negating.iter().copied().chain(extra.negating.iter().map(String::as_str))),
+        }
+    }
+
+    /// Keywords in the text just before and after a match
+    pub fn find(&self, before: &str, after: &str) -> KeywordMatches {
+        KeywordMatches {
+            supporting: find_keywords(self.supporting.as_ref(), before, after),
+            negating: find_keywords(self.negating.as_ref(), before, after),
+        }
+    }
+}
+
+/// Case-insensitive regex matching any keyword as whole words, with any run
+/// of whitespace between its words. Longer keywords are tried first, so
+/// "order #" wins over "order".
+fn keyword_regex<'a>(keywords: impl Iterator<Item = &'a str>) -> Option<Regex> {
+    let mut keywords: Vec<&str> = keywords.collect();
+    keywords.sort_by_key(|keyword| std::cmp::Reverse(keyword.len()));
+    let alternatives: Vec<String> = keywords
+        .into_iter()
+        .map(|keyword| {
+            let words: Vec<String> = keyword.split_whitespace().map(regex::escape).collect();
+            let end = if keyword.ends_with(|c: char| c.is_alphanumeric()) { r"\b" } else { "" };
+            format!(r"\b{}{}", words.join(r"\s+"), end)
+        })
+        .collect();
+    if alternatives.is_empty() {
+        return None;
+    }
+    Some(Regex::new(&format!("(?i)(?:{})", alternatives.join("|"))).unwrap())
+}
+
+/// Distinct keywords found, lowercased with single spaces between words
+fn find_keywords(regex: Option<&Regex>, before: &str, after: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for text in [before, after] {
+        for m in regex.into_iter().flat_map(|regex| regex.find_iter(text)) {
+            let keyword = m.as_str().split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+            if !found.contains(&keyword) {
+                found.push(keyword);
+            }
+        }
+    }
+    found
+}
+
+/// Whether `type_` has built-in keywords, so configuration can extend them
+pub fn is_keyword_type(type_: &str) -> bool {
+    DEFAULT_KEYWORDS.iter().any(|(name, _, _)| *name == type_)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyword_matching() {
+        let matcher = KeywordMatcher::new("ssn", None);
+
+        let found = matcher.find("Social\n Security: ", " (DOB 1980)");
+        assert_eq!(found.supporting, vec!["social security", "dob"]);
+        assert!(found.negating.is_empty());
+
+        let found = matcher.find("Order #", "");
+        assert_eq!(found.negating, vec!["order #"]);
+        assert!(!found.supported());
+
+        // Whole words only
+        assert!(matcher.find("lessn dobby", "").supporting.is_empty());
+    }
+
+    #[test]
+    fn test_configured_keywords_extend_the_defaults() {
+        let extra = ContextKeywords { supporting: vec!["Patient ID".to_string()], negating: vec!["po".to_string()] };
+        let matcher = KeywordMatcher::new("ssn", Some(&extra));
+
+        let found = matcher.find("patient  id 1, ssn", "PO");
+        assert_eq!(found.supporting, vec!["patient id", "ssn"]);
+        assert_eq!(found.negating, vec!["po"]);
+
+        assert!(is_keyword_type("bank_account"));
+        assert!(!is_keyword_type("passport"));
+    }
+}
@@ -1,9 +1,25 @@
 pub mod frequency;
 pub mod phrases;
+pub mod normalize;
 pub mod pii;
+pub mod keywords;
+pub mod custom;
+pub mod checksum;
+pub mod government_ids;
+pub mod financial;
+pub mod network;
+pub mod secrets;
 pub mod entropy;
 
 pub use frequency::*;
 pub use phrases::*;
+pub use normalize::*;
 pub use pii::*;
+pub use keywords::*;
+pub use custom::*;
+pub use checksum::*;
+pub use government_ids::*;
+pub use financial::*;
+pub use network::*;
+pub use secrets::*;
 pub use entropy::*;
@@ -0,0 +1,152 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+use regex::Regex;
+use lazy_static::lazy_static;
+
+// Validation and classification of network identifiers. IP addresses are
+// classified by the IANA special-purpose ranges they fall in, so policies can
+// weight a public address above a loopback or documentation one:
+//
+// - `public`: globally routable
+// - `private`: RFC 1918, carrier-grade NAT (RFC 6598) and IPv6 unique local
+// - `loopback`, `link_local`
+// - `documentation`: the example ranges of RFC 5737, RFC 3849 and RFC 9637
+// - `reserved`: unspecified, multicast, benchmarking and unassigned space
+
+lazy_static! {
+    // Text right before an IPv4 address embedded in an IPv6 one
+    static ref IPV6_PREFIX_PATTERN: Regex = Regex::new(r"(?i)(?::ffff(?::0{1,4})?:|::)$").unwrap();
+    static ref DOMAIN_SUFFIX_PATTERN: Regex = Regex::new(r"^[a-z0-9](?:[a-z0-9-]*[a-z0-9])?(?:\.[a-z0-9](?:[a-z0-9-]*[a-z0-9])?)*$").unwrap();
+}
+
+/// Class of an IPv4 address
+pub fn classify_ipv4(ip: Ipv4Addr) -> &'static str {
+    match ip.octets() {
+        [10, ..] | [172, 16..=31, ..] | [192, 168, ..] | [100, 64..=127, ..] => "private",
+        [127, ..] => "loopback",
+        [169, 254, ..] => "link_local",
+        [192, 0, 2, _] | [198, 51, 100, _] | [203, 0, 113, _] => "documentation",
+        [0, ..] | [198, 18..=19, ..] | [224..=255, ..] => "reserved",
+        _ => "public",
+    }
+}
+
+/// Class of an IPv6 address; IPv4-mapped addresses take the class of the
+/// IPv4 address
+pub fn classify_ipv6(ip: Ipv6Addr) -> &'static str {
+    if let Some(ipv4) = ip.to_ipv4_mapped() {
+        return classify_ipv4(ipv4);
+    }
+    let segments = ip.segments();
+    match segments[0] {
+        _ if ip.is_loopback() => "loopback",
+        _ if ip.is_unspecified() => "reserved",
+        s if s & 0xffc0 == 0xfe80 => "link_local",
+        s if s & 0xfe00 == 0xfc00 => "private",
+        0x2001 if segments[1] == 0x0db8 => "documentation",
+        0x3fff if segments[1] & 0xf000 == 0 => "documentation",
+        s if s & 0xe000 == 0x2000 => "public",
+        _ => "reserved",
+    }
+}
+
+/// Class of a dotted IPv4 address, `None` when an octet is out of range or
+/// the address is the tail of an IPv4-mapped IPv6 address written before it
+pub fn ipv4_classification(ip: &str, before: &str) -> Option<&'static str> {
+    if IPV6_PREFIX_PATTERN.is_match(before) {
+        return None;
+    }
+    let octets: Vec<u8> = ip.split('.').map(str::parse).collect::<Result<_, _>>().ok()?;
+    let octets: [u8; 4] = octets.try_into().ok()?;
+    Some(classify_ipv4(Ipv4Addr::from(octets)))
+}
+
+/// Class of an IPv6 address in any textual form: full, compressed with `::`,
+/// or with a dotted IPv4 tail. `None` for the unspecified address, which a
+/// bare `::` in code or grammar notation would otherwise parse as
+pub fn ipv6_classification(ip: &str) -> Option<&'static str> {
+    ip.parse::<Ipv6Addr>().ok().filter(|ip| !ip.is_unspecified()).map(classify_ipv6)
+}
+
+/// MAC address: six octets. The all-zero and broadcast addresses identify
+/// no device.
+pub fn mac_address_confidence(mac: &str) -> Option<f64> {
+    let digits: String = mac.chars().filter(char::is_ascii_hexdigit).collect::<String>().to_lowercase();
+    let anonymous = digits.chars().all(|c| c == '0') || digits.chars().all(|c| c == 'f');
+    (!anonymous).then_some(0.85)
+}
+
+/// Whether `suffix` is a valid domain suffix, ignoring a leading dot and case
+pub fn is_domain_suffix(suffix: &str) -> bool {
+    DOMAIN_SUFFIX_PATTERN.is_match(&suffix.trim_start_matches('.').to_ascii_lowercase())
+}
+
+/// Whether `host` is a name under one of `suffixes`. An address after `@` is
+/// the domain of an email address, reported as such.
+pub fn is_internal_hostname(host: &str, before: &str, suffixes: &[String]) -> bool {
+    if before.ends_with('@') || host.split('.').any(|label| label.starts_with('-') || label.ends_with('-')) {
+        return false;
+    }
+    let host = host.to_ascii_lowercase();
+    suffixes.iter().any(|suffix| {
+        let suffix = suffix.trim_start_matches('.').to_ascii_lowercase();
+        host.strip_suffix(&suffix).is_some_and(|name| name.ends_with('.'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipv4_classes() {
+        let cases = [
+            ("8.8.8.8", "public"),
+            ("10.1.2.3", "private"),
+            ("172.31.255.1", "private"),
+            ("172.32.0.1", "public"),
+            ("100.64.0.1", "private"),
+            ("127.0.0.1", "loopback"),
+            ("169.254.10.20", "link_local"),
+            ("203.0.113.7", "documentation"),
+            ("255.255.255.255", "reserved"),
+        ];
+        for (ip, class) in cases {
+            assert_eq!(ipv4_classification(ip, ""), Some(class), "{}", ip);
+        }
+        assert_eq!(ipv4_classification("256.1.1.1", ""), None);
+        assert_eq!(ipv4_classification("192.168.1.1", "addr ::ffff:"), None);
+    }
+
+    #[test]
+    fn test_ipv6_classes() {
+        let cases = [
+            ("2606:4700:4700::1111", "public"),
+            ("::1", "loopback"),
+            ("fe80::1ff:fe23:4567:890a", "link_local"),
+            ("fd12:3456:789a::1", "private"),
+            ("2001:db8:85a3::8a2e:370:7334", "documentation"),
+            ("ff02::1", "reserved"),
+            ("::ffff:192.168.1.1", "private"),
+            ("::ffff:8.8.8.8", "public"),
+        ];
+        for (ip, class) in cases {
+            assert_eq!(ipv6_classification(ip), Some(class), "{}", ip);
+        }
+        assert_eq!(ipv6_classification("12:30:45"), None);
+        assert_eq!(ipv6_classification("1::2::3"), None);
+        assert_eq!(ipv6_classification("::"), None);
+    }
+
+    #[test]
+    fn test_internal_hostnames() {
+        let suffixes = vec![".corp.example.com".to_string(), "INTERNAL".to_string()];
+        assert!(is_internal_hostname("build01.corp.example.com", "", &suffixes));
+        assert!(is_internal_hostname("db.Prod.internal", "", &suffixes));
+        assert!(!is_internal_hostname("corp.example.com", "", &suffixes));
+        assert!(!is_internal_hostname("www.example.com", "", &suffixes));
+        assert!(!is_internal_hostname("mail.corp.example.com", "jo@", &suffixes));
+
+        assert!(is_domain_suffix(".corp.example.com"));
+        assert!(!is_domain_suffix("corp example"));
+    }
+}
@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use unicode_normalization::char::decompose_compatible;
+use unicode_security::skeleton;
+
+// Normalization that sees through disguised banned phrases: "c0nf1dential",
+// "C O N F I D E N T I A L", "conf-idential" or Cyrillic look-alike letters.
+// Phrases and text are both reduced to a skeleton, char by char, so every
+// skeleton char can be traced back to the original char it came from.
+// Leetspeak substitutes can stand for several letters, so the automaton
+// matches on a coarser search key and each match is then checked letter by
+// letter with `reads_as`.
+
+/// Longest run of separators still treated as inside a word
+pub const MAX_SEPARATOR_RUN: usize = 3;
+
+/// Steps applied to banned phrases and text before matching
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PhraseNormalization {
+    /// Match phrases through the steps below; off by default
+    pub enabled: bool,
+    /// Decompose compatibility forms as NFKC does: fullwidth, mathematical,
+    /// circled, superscript and subscript characters and ligatures
+    pub compatibility: bool,
+    /// Replace non-ASCII characters by their UTS #39 confusable skeleton
+    pub confusables: bool,
+    /// Read digits and symbols standing in for letters ("c0nf1d3nt14l")
+    pub leetspeak: bool,
+    /// Ignore up to `MAX_SEPARATOR_RUN` spaces, punctuation, zero-width or
+    /// combining characters between letters
+    pub separators: bool,
+}
+
+impl Default for PhraseNormalization {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            compatibility: true,
+            confusables: true,
+            leetspeak: true,
+            separators: true,
+        }
+    }
+}
+
+impl PhraseNormalization {
+    /// Whether `c` is skipped between letters
+    pub fn is_separator(&self, c: char) -> bool {
+        self.separators
+            && (c.is_whitespace()
+                || matches!(c, '-' | '_' | '.' | ',' | '*' | '~' | '/' | '\\' | '\'' | '"' | '·' | '•' | '\u{ad}')
+                || matches!(c, '\u{200b}'..='\u{200d}' | '\u{2060}' | '\u{feff}' | '\u{300}'..='\u{36f}'))
+    }
+
+    /// Append the skeleton of `c`, which must not be a separator. Separators
+    /// it decomposes into, such as the accent of 'é', are dropped, so the
+    /// skeleton may be empty
+    pub fn push_skeleton(&self, c: char, out: &mut String) {
+        self.push(c, false, out);
+    }
+
+    /// Append the search key of `c`: its skeleton with each leetspeak
+    /// substitute and the letters it may stand for merged into one char
+    pub fn push_search_key(&self, c: char, out: &mut String) {
+        self.push(c, self.leetspeak, out);
+    }
+
+    fn push(&self, c: char, merge_leet: bool, out: &mut String) {
+        let mut emit = |d: char| {
+            let d = fold_char(d);
+            let key = |d: char| if merge_leet { leet_class(d) } else { d };
+            if !self.confusables || d.is_ascii() {
+                out.push(key(d));
+                return;
+            }
+            let mut buf = [0; 4];
+            for p in skeleton(d.encode_utf8(&mut buf)).filter(|&p| !self.is_separator(p)) {
+                out.push(key(fold_char(p)));
+            }
+        };
+        if !self.compatibility || c.is_ascii() {
+            emit(c);
+            return;
+        }
+        decompose_compatible(c, |d| {
+            if d == c || !self.is_separator(d) {
+                emit(d);
+            }
+        });
+    }
+
+    /// Skeleton of a whole phrase, with its separators dropped
+    pub fn skeleton(&self, phrase: &str) -> String {
+        let mut out = String::new();
+        for c in phrase.chars().filter(|&c| !self.is_separator(c)) {
+            self.push_skeleton(c, &mut out);
+        }
+        out
+    }
+
+    /// Search key of a whole phrase, with its separators dropped
+    pub fn search_key(&self, phrase: &str) -> String {
+        let mut out = String::new();
+        for c in phrase.chars().filter(|&c| !self.is_separator(c)) {
+            self.push_search_key(c, &mut out);
+        }
+        out
+    }
+
+    /// Whether `text`, a match of the search key of `phrase`, reads as the
+    /// phrase: each of its skeleton chars is the phrase's char or, with
+    /// leetspeak, a substitute for it
+    pub fn reads_as(&self, text: &str, phrase: &str) -> bool {
+        let text = self.skeleton(text);
+        let phrase = self.skeleton(phrase);
+        text.chars().count() == phrase.chars().count()
+            && text.chars().zip(phrase.chars()).all(|(t, p)| t == p || (self.leetspeak && leet(t).contains(&p)))
+    }
+}
+
+/// Char text is compared by when matching ignores case only: its lowercase
+/// form, with the variant lowercase letters merged, as `(?i)` does
+pub fn fold_char(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    let folded = match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        // Lowercases to several chars, e.g. 'İ'; only matches itself
+        _ => c,
+    };
+    match folded {
+        'ς' => 'σ',
+        'ſ' => 's',
+        'ϐ' => 'β',
+        'ϑ' => 'θ',
+        'ϕ' => 'φ',
+        'ϖ' => 'π',
+        'ϰ' => 'κ',
+        'ϱ' => 'ρ',
+        'ϵ' => 'ε',
+        '\u{1fbe}' => 'ι',
+        other => other,
+    }
+}
+
+/// Whether `text` reads as `phrase` ignoring only case and how the words
+/// are spaced
+pub fn plain_match(text: &str, phrase: &str) -> bool {
+    let words = |s: &str| s.split_whitespace().map(|w| w.chars().map(fold_char).collect::<String>()).collect::<Vec<_>>();
+    words(text) == words(phrase)
+}
+
+/// Letters a digit, symbol or letter may stand for. '1' and '|' read as 'i'
+/// or 'l', and 'i' and 'l' as each other, as in "confidentiaI"
+fn leet(c: char) -> &'static [char] {
+    match c {
+        '0' => &['o'],
+        '1' | '|' => &['i', 'l'],
+        '!' | 'l' => &['i'],
+        'i' => &['l'],
+        '2' => &['z'],
+        '3' => &['e'],
+        '4' | '@' => &['a'],
+        '5' | '$' => &['s'],
+        '6' => &['g', 'b'],
+        '7' => &['t', 'l'],
+        '8' => &['b'],
+        '9' => &['g', 'q'],
+        _ => &[],
+    }
+}
+
+/// One char for every letter and substitute `leet` links together, directly
+/// or through a shared substitute
+fn leet_class(c: char) -> char {
+    match c {
+        '0' => 'o',
+        'l' | 't' | '1' | '|' | '!' | '7' => 'i',
+        '2' => 'z',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        'b' | 'q' | '6' | '8' | '9' => 'g',
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disguises_read_as_the_phrase() {
+        let normalization = PhraseNormalization { enabled: true, ..PhraseNormalization::default() };
+        let expected = normalization.search_key("confidential");
+        assert_eq!(normalization.skeleton("confidential"), "confidential");
+
+        for disguised in [
+            "c0nf1dential",
+            "confidentia1",
+            "C O N F I D E N T I A L",
+            "CONFIDENTIAI",
+            "conf-idential",
+            "\u{441}\u{43e}nfid\u{435}nti\u{430}l",
+            "ｃｏｎｆｉｄｅｎｔｉａｌ",
+            "𝐜𝐨𝐧𝐟𝐢𝐝𝐞𝐧𝐭𝐢𝐚𝐥",
+            "ⓒⓞⓝⓕⓘⓓⓔⓝⓣⓘⓐⓛ",
+            "con\u{200b}ﬁdential",
+            "c\u{332}o\u{332}nfidential",
+            "cönfidéntial",
+        ] {
+            assert_eq!(normalization.search_key(disguised), expected, "{}", disguised);
+            assert!(normalization.reads_as(disguised, "confidential"), "{}", disguised);
+        }
+        assert_eq!(normalization.skeleton("do not share"), "donotshare");
+    }
+
+    #[test]
+    fn test_substitutes_read_as_each_of_their_letters() {
+        let normalization = PhraseNormalization { enabled: true, ..PhraseNormalization::default() };
+        assert!(normalization.reads_as("6ag", "bag"));
+        assert!(normalization.reads_as("6ag", "gag"));
+        assert!(normalization.reads_as("7oo1", "tool"));
+        assert!(normalization.reads_as("ki11", "kill"));
+
+        // Letters sharing a substitute still differ from each other
+        assert_eq!(normalization.search_key("bag"), normalization.search_key("gag"));
+        assert!(!normalization.reads_as("bag", "gag"));
+        assert!(!normalization.reads_as("quiet", "guiet"));
+        assert!(!normalization.reads_as("confidentia", "confidential"));
+    }
+
+    #[test]
+    fn test_steps_can_be_turned_off() {
+        let normalization = PhraseNormalization { leetspeak: false, separators: false, ..PhraseNormalization::default() };
+        assert_eq!(normalization.skeleton("C0NF-іdential"), "c0nf-idential");
+        assert_eq!(normalization.search_key("C0NF-іdential"), "c0nf-idential");
+        assert!(!normalization.reads_as("c0nfidential", "confidential"));
+        assert!(!normalization.is_separator(' '));
+
+        let plain = PhraseNormalization { compatibility: false, confusables: false, ..normalization };
+        assert_eq!(plain.skeleton("ｃ\u{43e}ﬁ"), "ｃ\u{43e}ﬁ");
+
+        assert!(plain_match("Do  NOT\nshare", "do not share"));
+        assert!(!plain_match("do-not share", "do not share"));
+    }
+}
@@ -1,65 +1,427 @@
-use crate::types::BannedPhraseMatch;
+use std::collections::VecDeque;
+use std::fmt;
+use aho_corasick::automaton::{Automaton, StateID};
+use aho_corasick::nfa::contiguous::NFA;
+use aho_corasick::{Anchored, MatchKind};
+use regex::Regex;
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::analysis::custom::{bounded_regex_len, compile_bounded_regex};
+use crate::analysis::normalize::{fold_char, plain_match, PhraseNormalization, MAX_SEPARATOR_RUN};
+use crate::types::{BannedPhraseMatch, Decision, Severity, TextLocation};
+use crate::utils::stream::{ChunkWindow, MAX_CHAR_BYTES};
 
 pub const BANNED_PHRASES: &[&str] = &["confidential", "do not share"];
 
-pub fn detect_banned_phrases(text: &str) -> Vec<BannedPhraseMatch> {
-    let mut matches = Vec::new();
-    let text_lower = text.to_lowercase();
-    
-    for phrase in BANNED_PHRASES {
-        let phrase_lower = phrase.to_lowercase();
-        let mut start = 0;
-        
-        while let Some(pos) = text_lower[start..].find(&phrase_lower) {
-            let actual_pos = start + pos;
-            
-            // Check if this is a word boundary match
-            let is_word_boundary = {
-                let before_char = if actual_pos > 0 { 
-                    text_lower.chars().nth(actual_pos - 1) 
-                } else { 
-                    None 
-                };
-                let after_char = text_lower.chars().nth(actual_pos + phrase.len());
-                
-                let before_ok = before_char.is_none() || !before_char.unwrap().is_alphanumeric();
-                let after_ok = after_char.is_none() || !after_char.unwrap().is_alphanumeric();
-                
-                before_ok && after_ok
-            };
-            
-            if is_word_boundary {
-                // Get context around the match
-                let context_start = actual_pos.saturating_sub(20);
-                let context_end = (actual_pos + phrase.len() + 20).min(text.len());
-                let context = &text[context_start..context_end];
-                
-                // Determine severity based on context
-                let severity = if text_lower[actual_pos..actual_pos + phrase.len()] == *phrase_lower {
-                    "high"
-                } else {
-                    "medium"
-                };
-                
-                matches.push(BannedPhraseMatch {
-                    phrase: phrase.to_string(),
-                    position: actual_pos,
-                    context: context.to_string(),
-                    severity: severity.to_string(),
-                });
+/// Bytes of surrounding text captured on each side of a match
+pub const CONTEXT_RADIUS: usize = 20;
+
+/// Longest match a regex, word or glob rule may produce
+pub const MAX_RULE_MATCH_BYTES: usize = 1024;
+
+/// Whitespace chars allowed between the words of a `word` rule
+const MAX_WORD_GAP: usize = 16;
+
+/// Non-whitespace chars a glob `*` matches at most
+const MAX_GLOB_STAR: usize = 32;
+
+/// How a rule's `pattern` is matched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhrasePatternType {
+    /// The exact text
+    #[default]
+    Literal,
+    /// The words of the pattern in order, separated by any whitespace
+    Word,
+    /// A bounded regular expression
+    Regex,
+    /// `*` matches up to 32 non-whitespace chars, `?` exactly one
+    Glob,
+}
+
+/// A banned phrase rule from `StreamingConfig::banned_phrases`. A plain
+/// string is a case-insensitive, whole-word literal of high severity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self", deny_unknown_fields)]
+pub struct BannedPhraseRule {
+    pub pattern: String,
+    #[serde(default, rename = "type")]
+    pub type_: PhrasePatternType,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Only match when not preceded or followed by a letter or digit
+    #[serde(default = "default_true")]
+    pub whole_word: bool,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    /// Label reported with each match, e.g. `trade_secret`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Evidence each match adds to the banned phrase detector
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_severity() -> Severity {
+    Severity::High
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+impl BannedPhraseRule {
+    /// Case-insensitive, whole-word literal of high severity
+    pub fn literal(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            type_: PhrasePatternType::Literal,
+            case_sensitive: false,
+            whole_word: true,
+            severity: Severity::High,
+            category: None,
+            weight: 1.0,
+        }
+    }
+
+    /// Matched by the shared automaton rather than a regex of its own
+    fn is_automaton_literal(&self) -> bool {
+        self.type_ == PhrasePatternType::Literal && !self.case_sensitive
+    }
+
+    /// Regex matching the rule, for every rule but automaton literals
+    fn regex_source(&self) -> String {
+        let body = match self.type_ {
+            PhrasePatternType::Literal => regex::escape(&self.pattern),
+            PhrasePatternType::Word => self
+                .pattern
+                .split_whitespace()
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join(&format!(r"\s{{1,{}}}", MAX_WORD_GAP)),
+            PhrasePatternType::Regex => self.pattern.clone(),
+            PhrasePatternType::Glob => {
+                let mut body = String::new();
+                for c in self.pattern.chars() {
+                    match c {
+                        '*' => body.push_str(&format!(r"\S{{0,{}}}", MAX_GLOB_STAR)),
+                        '?' => body.push_str(r"\S"),
+                        c => body.push_str(&regex::escape(&c.to_string())),
+                    }
+                }
+                body
+            }
+        };
+        if self.case_sensitive {
+            body
+        } else {
+            format!("(?i:{})", body)
+        }
+    }
+
+    fn validate(&self, path: &str) -> Result<(), String> {
+        if self.pattern.trim().is_empty() {
+            return Err(format!("{}.pattern must not be empty", path));
+        }
+        if !self.weight.is_finite() || self.weight < 0.0 {
+            return Err(format!("{}.weight must be a finite number >= 0, got {}", path, self.weight));
+        }
+        if self.category.as_ref().is_some_and(|category| category.trim().is_empty()) {
+            return Err(format!("{}.category must not be empty", path));
+        }
+        if self.is_automaton_literal() {
+            return Ok(());
+        }
+        bounded_regex_len(&self.regex_source(), &format!("{}.pattern", path), MAX_RULE_MATCH_BYTES).map(|_| ())
+    }
+}
+
+impl Serialize for BannedPhraseRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BannedPhraseRule::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BannedPhraseRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RuleVisitor;
+
+        impl<'de> Visitor<'de> for RuleVisitor {
+            type Value = BannedPhraseRule;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a phrase or a banned phrase rule object")
+            }
+
+            fn visit_str<E: de::Error>(self, phrase: &str) -> Result<Self::Value, E> {
+                Ok(BannedPhraseRule::literal(phrase))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                BannedPhraseRule::deserialize(de::value::MapAccessDeserializer::new(map))
+            }
+        }
+
+        deserializer.deserialize_any(RuleVisitor)
+    }
+}
+
+/// Check every banned phrase rule, naming the offending field
+pub fn validate_rules(rules: &[BannedPhraseRule]) -> Result<(), String> {
+    for (i, rule) in rules.iter().enumerate() {
+        rule.validate(&format!("banned_phrases[{}]", i))?;
+    }
+    Ok(())
+}
+
+/// Fed to the automaton in place of a separator run too long to be inside a
+/// word; never part of a phrase, as it is not valid UTF-8
+const BREAK: u8 = 0xff;
+
+#[derive(Debug)]
+struct RegexRule {
+    /// Index of the rule in `PhraseScanner::rules`
+    rule: usize,
+    regex: Regex,
+    max_match_bytes: usize,
+    cursor: usize,
+}
+
+/// Banned phrase matcher shared by one-shot and streaming analysis.
+///
+/// Case-insensitive literals, usually the bulk of a dictionary, are compiled
+/// into one Aho-Corasick automaton that is fed each byte of text once,
+/// carrying its state across chunks, so the cost of a scan does not grow
+/// with the size of the dictionary. Other rules each run a bounded regex
+/// with its own resume cursor. Either way, chunked scans report exactly the
+/// matches a single pass over the whole text would.
+///
+/// With normalization enabled, literals are matched by their search keys
+/// instead and checked letter by letter, and matches that differ from the
+/// phrase by more than case and spacing are flagged as evasion attempts.
+#[derive(Debug)]
+pub struct PhraseScanner {
+    rules: Vec<BannedPhraseRule>,
+    normalization: Option<PhraseNormalization>,
+    automaton: Option<NFA>,
+    /// Rule index of each automaton pattern
+    automaton_rules: Vec<usize>,
+    state: StateID,
+    /// Absolute offset up to which text has been fed to the automaton
+    fed: usize,
+    /// Separators skipped since the last char fed
+    separator_run: usize,
+    /// Start offset of the original char behind each of the last folded
+    /// bytes fed, enough to locate the start of the longest phrase
+    origins: VecDeque<usize>,
+    /// Automaton matches found but not reported yet, as (start, end, rule)
+    pending: Vec<(usize, usize, usize)>,
+    regexes: Vec<RegexRule>,
+}
+
+impl PhraseScanner {
+    /// Scanner for case-insensitive, whole-word literal phrases
+    pub fn new<S: AsRef<str>>(phrases: &[S]) -> Self {
+        let rules: Vec<BannedPhraseRule> = phrases.iter().map(|phrase| BannedPhraseRule::literal(phrase.as_ref())).collect();
+        Self::with_rules(&rules, &PhraseNormalization::default()).expect("literal rules always compile")
+    }
+
+    /// Scanner for `rules`, matching literals through `normalization` when
+    /// it is enabled
+    pub fn with_rules(rules: &[BannedPhraseRule], normalization: &PhraseNormalization) -> Result<Self, String> {
+        let normalization = normalization.enabled.then(|| normalization.clone());
+        let fold = |phrase: &str| match &normalization {
+            Some(normalization) => normalization.search_key(phrase),
+            None => phrase.chars().map(fold_char).collect(),
+        };
+
+        let mut automaton_rules = Vec::new();
+        let mut folded = Vec::new();
+        let mut regexes = Vec::new();
+        for (index, rule) in rules.iter().enumerate() {
+            if rule.is_automaton_literal() {
+                let skeleton = fold(&rule.pattern);
+                if !skeleton.is_empty() {
+                    automaton_rules.push(index);
+                    folded.push(skeleton);
+                }
+            } else if !rule.pattern.is_empty() {
+                let field = format!("banned_phrases[{}].pattern", index);
+                let (regex, max_match_bytes) = compile_bounded_regex(&rule.regex_source(), &field, MAX_RULE_MATCH_BYTES)?;
+                regexes.push(RegexRule { rule: index, regex, max_match_bytes, cursor: 0 });
+            }
+        }
+        let automaton = (!folded.is_empty()).then(|| {
+            NFA::builder()
+                .match_kind(MatchKind::Standard)
+                .build(&folded)
+                .expect("phrase automaton within size limits")
+        });
+        let state = automaton
+            .as_ref()
+            .map_or(StateID::ZERO, |nfa| nfa.start_state(Anchored::No).unwrap());
+
+        Ok(Self {
+            rules: rules.to_vec(),
+            normalization,
+            automaton,
+            automaton_rules,
+            state,
+            fed: 0,
+            separator_run: 0,
+            origins: VecDeque::new(),
+            pending: Vec::new(),
+            regexes,
+        })
+    }
+
+    /// Widest match in bytes, allowing for case folding to wider chars and,
+    /// when normalizing, separators between every char
+    pub fn max_match_bytes(&self) -> usize {
+        let per_char = match &self.normalization {
+            Some(_) => MAX_CHAR_BYTES * (1 + MAX_SEPARATOR_RUN),
+            None => MAX_CHAR_BYTES,
+        };
+        let literal = self.automaton.as_ref().map_or(0, |nfa| nfa.max_pattern_len() * per_char);
+        self.regexes.iter().map(|r| r.max_match_bytes).fold(literal, usize::max)
+    }
+
+    /// Earliest position a reported match may still start at
+    pub fn oldest_cursor(&self) -> Option<usize> {
+        let literal = self.automaton.as_ref().map(|_| {
+            let partial = self.origins.front().copied().unwrap_or(self.fed);
+            self.pending.iter().map(|&(start, _, _)| start).fold(partial, usize::min)
+        });
+        literal.into_iter().chain(self.regexes.iter().map(|r| r.cursor)).min()
+    }
+
+    /// Feed the text not seen yet through the automaton
+    fn feed(&mut self, window: &ChunkWindow) {
+        let Some(nfa) = &self.automaton else { return };
+        let text = window.slice(self.fed, window.end());
+        let mut folded = String::new();
+        let mut step = |byte: u8, offset: usize, end: usize| {
+            self.state = nfa.next_state(Anchored::No, self.state, byte);
+            if self.origins.len() == nfa.max_pattern_len() {
+                self.origins.pop_front();
+            }
+            self.origins.push_back(offset);
+            if !nfa.is_match(self.state) {
+                return;
+            }
+            for index in 0..nfa.match_len(self.state) {
+                let pattern = nfa.match_pattern(self.state, index);
+                let start = self.origins[self.origins.len() - nfa.pattern_len(pattern)];
+                self.pending.push((start, end, self.automaton_rules[pattern.as_usize()]));
+            }
+        };
+        for (i, c) in text.char_indices() {
+            let offset = self.fed + i;
+            let end = offset + c.len_utf8();
+            folded.clear();
+            match &self.normalization {
+                Some(normalization) => {
+                    if !normalization.is_separator(c) {
+                        normalization.push_search_key(c, &mut folded);
+                    }
+                    // Chars with an empty skeleton are skipped like separators
+                    if folded.is_empty() {
+                        self.separator_run += 1;
+                        continue;
+                    }
+                    if std::mem::take(&mut self.separator_run) > MAX_SEPARATOR_RUN {
+                        step(BREAK, offset, end);
+                    }
+                }
+                None => folded.push(fold_char(c)),
+            }
+            for &byte in folded.as_bytes() {
+                step(byte, offset, end);
             }
-            
-            start = actual_pos + 1;
         }
+        self.fed = window.end();
     }
-    
-    matches
+
+    /// Report matches starting before `limit`, ordered by position
+    pub fn scan(&mut self, window: &ChunkWindow, limit: usize) -> Vec<BannedPhraseMatch> {
+        self.feed(window);
+
+        let (mut spans, pending): (Vec<_>, Vec<_>) = self.pending.iter().partition(|&&(start, _, _)| start < limit);
+        self.pending = pending;
+        for regex in &mut self.regexes {
+            for (start, end) in window.find_settled_overlapping(&regex.regex, &mut regex.cursor, limit) {
+                spans.push((start, end, regex.rule));
+            }
+        }
+        // Check if this is a word boundary match
+        spans.retain(|&(start, end, rule)| {
+            !self.rules[rule].whole_word
+                || (window.char_before(start).is_none_or(|c| !c.is_alphanumeric())
+                    && window.char_at(end).is_none_or(|c| !c.is_alphanumeric()))
+        });
+        // A search key match may put a substitute in place of a letter it
+        // does not stand for
+        if let Some(normalization) = &self.normalization {
+            spans.retain(|&(start, end, rule)| {
+                !self.rules[rule].is_automaton_literal()
+                    || normalization.reads_as(window.slice(start, end), &self.rules[rule].pattern)
+            });
+        }
+        spans.sort_unstable();
+
+        let mut locator = window.locator();
+        spans
+            .into_iter()
+            .map(|(start, end, index)| {
+                let rule = &self.rules[index];
+                let evasion = self.normalization.is_some()
+                    && rule.is_automaton_literal()
+                    && !plain_match(window.slice(start, end), &rule.pattern);
+                BannedPhraseMatch {
+                    id: String::new(),
+                    phrase: rule.pattern.clone(),
+                    position: start,
+                    context: window.context(start, end, CONTEXT_RADIUS).to_string(),
+                    severity: rule.severity.as_str().to_string(),
+                    location: TextLocation::from_span(&mut locator, window.relative(start), window.relative(end)),
+                    recommended_action: Decision::Allow,
+                    evasion,
+                    category: rule.category.clone(),
+                    weight: rule.weight,
+                }
+            })
+            .collect()
+    }
+}
+
+pub fn detect_banned_phrases(text: &str) -> Vec<BannedPhraseMatch> {
+    let mut window = ChunkWindow::new();
+    window.push(text);
+    PhraseScanner::new(BANNED_PHRASES).scan(&window, window.end())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn literals(phrases: &[&str]) -> Vec<BannedPhraseRule> {
+        phrases.iter().map(|phrase| BannedPhraseRule::literal(phrase)).collect()
+    }
+
+    fn scan_rules(json: &str, text: &str) -> Vec<BannedPhraseMatch> {
+        let rules: Vec<BannedPhraseRule> = serde_json::from_str(json).unwrap();
+        validate_rules(&rules).unwrap();
+        let mut scanner = PhraseScanner::with_rules(&rules, &PhraseNormalization::default()).unwrap();
+        let mut window = ChunkWindow::new();
+        window.push(text);
+        scanner.scan(&window, window.end())
+    }
+
     #[test]
     fn test_basic_phrase_detection() {
         let text = "This document is confidential and should not be shared.";
@@ -252,9 +614,151 @@ mod tests {
         let text = "confidential café résumé do not share naïve";
         let matches = detect_banned_phrases(text);
         
-        assert_eq!(matches.len(), 1);
+        assert_eq!(matches.len(), 2);
         assert_eq!(matches[0].phrase, "confidential");
-        // "do not share" is not detected as a complete phrase
+        assert_eq!(matches[1].phrase, "do not share");
+        assert_eq!(matches[1].position, 28);
+    }
+
+    #[test]
+    fn test_scanner_matches_across_chunks() {
+        let mut scanner = PhraseScanner::new(&["do not share"]);
+        let mut window = ChunkWindow::new();
+
+        window.push("Please do not");
+        assert!(scanner.scan(&window, window.settled_limit(60, false)).is_empty());
+        window.push(" share this");
+        let matches = scanner.scan(&window, window.end());
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].position, 7);
+    }
+
+    #[test]
+    fn test_large_dictionary() {
+        let mut phrases: Vec<String> = (0..5000).map(|i| format!("project {} codename", i)).collect();
+        phrases.push("codename".to_string());
+        let mut scanner = PhraseScanner::new(&phrases);
+        let mut window = ChunkWindow::new();
+        window.push("Ask about PROJECT 4999 Codename and project 12 codenames.");
+        let matches = scanner.scan(&window, window.end());
+
+        // Overlapping phrases are all reported; "codenames" is not a whole word
+        let found: Vec<(&str, usize)> = matches.iter().map(|m| (m.phrase.as_str(), m.position)).collect();
+        assert_eq!(found, vec![("project 4999 codename", 10), ("codename", 23)]);
+    }
+
+    #[test]
+    fn test_unicode_case_folding() {
+        let mut scanner = PhraseScanner::new(&["σίσυφος", "straße"]);
+        let mut window = ChunkWindow::new();
+        window.push("ΣΊΣΥΦΟΣ, σίσυφοσ and STRAẞE; sίσυφος");
+        let matches = scanner.scan(&window, window.end());
+
+        let found: Vec<(&str, usize)> = matches.iter().map(|m| (m.phrase.as_str(), m.position)).collect();
+        assert_eq!(found, vec![("σίσυφος", 0), ("σίσυφος", 16), ("straße", 35)]);
+    }
+
+    #[test]
+    fn test_normalized_matches_map_to_original_text() {
+        let normalization = PhraseNormalization { enabled: true, ..PhraseNormalization::default() };
+        let mut scanner = PhraseScanner::with_rules(&literals(&["confidential", "do not share"]), &normalization).unwrap();
+        let mut window = ChunkWindow::new();
+        window.push("Do  not share c0nf1dential, C O N F I D E N T I A L; c o n f     i d e n t i a l xc0nfidential");
+        let matches = scanner.scan(&window, window.end());
+
+        let found: Vec<(&str, &str, bool)> = matches
+            .iter()
+            .map(|m| (m.phrase.as_str(), window.slice(m.position, m.position + m.location.byte_length), m.evasion))
+            .collect();
+        // A long run of separators ends the word, as does a letter before it
+        assert_eq!(found, vec![
+            ("do not share", "Do  not share", false),
+            ("confidential", "c0nf1dential", true),
+            ("confidential", "C O N F I D E N T I A L", true),
+        ]);
+    }
+
+    #[test]
+    fn test_normalized_substitutes_match_only_their_letters() {
+        let normalization = PhraseNormalization { enabled: true, ..PhraseNormalization::default() };
+        let mut scanner = PhraseScanner::with_rules(&literals(&["confidential", "quiet"]), &normalization).unwrap();
+        let mut window = ChunkWindow::new();
+        window.push("conf1dentia1, CONFIDENTIAI, guiet and qu1et");
+        let matches = scanner.scan(&window, window.end());
+
+        let found: Vec<(&str, &str)> = matches
+            .iter()
+            .map(|m| (m.phrase.as_str(), window.slice(m.position, m.position + m.location.byte_length)))
+            .collect();
+        assert_eq!(found, vec![
+            ("confidential", "conf1dentia1"),
+            ("confidential", "CONFIDENTIAI"),
+            ("quiet", "qu1et"),
+        ]);
+    }
+
+    #[test]
+    fn test_normalized_scan_across_chunks() {
+        let normalization = PhraseNormalization { enabled: true, ..PhraseNormalization::default() };
+        let mut scanner = PhraseScanner::with_rules(&literals(&["secret"]), &normalization).unwrap();
+        let mut window = ChunkWindow::new();
+
+        window.push("it is $ e");
+        assert!(scanner.scan(&window, window.settled_limit(scanner.max_match_bytes(), false)).is_empty());
+        window.push(" c r 3 t!");
+        let matches = scanner.scan(&window, window.end());
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].position, 6);
+        assert_eq!(matches[0].location.byte_length, 11);
+        assert!(matches[0].evasion);
+    }
+
+    #[test]
+    fn test_rule_types() {
+        let matches = scan_rules(
+            r#"[
+                "internal use only",
+                {"pattern": "Trade Secret", "case_sensitive": true, "severity": "critical", "category": "ip", "weight": 2.5},
+                {"pattern": "project   x", "type": "word"},
+                {"pattern": "PRJ-\\d{4}", "type": "regex", "severity": "medium"},
+                {"pattern": "draft-v?", "type": "glob", "whole_word": false, "severity": "low"}
+            ]"#,
+            "Internal Use Only: trade secret, Trade Secret; PROJECT\n  X is PRJ-0042 (redraft-v2)",
+        );
+
+        let found: Vec<(&str, usize, &str)> = matches.iter().map(|m| (m.phrase.as_str(), m.position, m.severity.as_str())).collect();
+        assert_eq!(found, vec![
+            ("internal use only", 0, "high"),
+            ("Trade Secret", 33, "critical"),
+            ("project   x", 47, "high"),
+            ("PRJ-\\d{4}", 62, "medium"),
+            ("draft-v?", 74, "low"),
+        ]);
+        assert_eq!(matches[1].category.as_deref(), Some("ip"));
+        assert_eq!(matches[1].weight, 2.5);
+        assert_eq!(matches[2].location.byte_length, 11);
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let cases = [
+            (r#"[{"pattern": " "}]"#, "banned_phrases[0].pattern must not be empty"),
+            (r#"["ok", {"pattern": "x", "weight": -1}]"#, "banned_phrases[1].weight must be a finite number >= 0, got -1"),
+            (r#"[{"pattern": "x", "category": ""}]"#, "banned_phrases[0].category must not be empty"),
+            (
+                r#"[{"pattern": "secret.*", "type": "regex"}]"#,
+                "banned_phrases[0].pattern must match at most 1024 bytes; bound repetitions with {m,n} instead of + or *",
+            ),
+        ];
+        for (json, error) in cases {
+            let rules: Vec<BannedPhraseRule> = serde_json::from_str(json).unwrap();
+            assert_eq!(validate_rules(&rules).unwrap_err(), error);
+        }
+
+        let err = serde_json::from_str::<Vec<BannedPhraseRule>>(r#"[{"pattern": "x", "type": "fuzzy"}]"#).unwrap_err();
+        assert!(err.to_string().starts_with("unknown variant `fuzzy`"), "{}", err);
     }
 
     #[test]
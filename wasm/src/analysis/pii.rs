@@ -1,130 +1,755 @@
-use crate::types::PIIPattern;
+use crate::analysis::checksum::luhn_check;
+use crate::analysis::custom::CustomPiiPattern;
+use crate::analysis::financial::*;
+use crate::analysis::government_ids::*;
+use crate::analysis::keywords::*;
+use crate::analysis::network::*;
+use crate::types::{Decision, PIIAlternative, PIIPattern, TextLocation};
+use crate::utils::stream::ChunkWindow;
 use regex::Regex;
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 lazy_static! {
-    static ref PHONE_PATTERN: Regex = Regex::new(r"\b\d{3}[-.]?\d{3}[-.]?\d{4}\b").unwrap();
-    static ref SSN_PATTERN: Regex = Regex::new(r"\b\d{3}-\d{2}-\d{4}\b").unwrap();
-    static ref CREDIT_CARD_PATTERN: Regex = Regex::new(r"\b\d{4}[- ]?\d{4}[- ]?\d{4}[- ]?\d{4}\b").unwrap();
+    // International (+CC or 00CC), North American 3-3-4 with an optional
+    // parenthesized area code, or a national number with a trunk 0. One regex,
+    // so a number is reported once whichever formats it fits
+    static ref PHONE_PATTERN: Regex = Regex::new(concat!(
+        r"(?:\B\+|\b00)\d{1,3}(?:[ .-]?\(\d{1,4}\))?(?:[ .-]?\d{1,7}){1,6}\b",
+        r"|(?:\(\d{3}\) ?|\b\d{3}[-. ]?)\d{3}[-. ]?\d{4}\b",
+        r"|(?:\(0\d{1,4}\)[ -]?|\b0\d{1,4}[ -])\d{2,8}(?:[ -]\d{2,8}){0,3}\b",
+    )).unwrap();
+    static ref NANP_PHONE_PATTERN: Regex = Regex::new(r"^(?:\(\d{3}\) ?|\d{3}[-. ]?)\d{3}[-. ]?\d{4}$").unwrap();
+    static ref DATE_LIKE_PATTERN: Regex = Regex::new(r"^\d{2}[-. ]\d{2}[-. ]\d{4}$").unwrap();
+    static ref SSN_PATTERN: Regex = Regex::new(r"\b\d{3}-\d{2}-\d{4}\b|\b\d{3} \d{2} \d{4}\b|\b\d{9}\b").unwrap();
+    // 4-6-5 / 4-6-4 (Amex, Diners), 4-4-4-4 with an optional 3-digit group,
+    // 4-4-5, or 12 to 19 contiguous digits
+    static ref CREDIT_CARD_PATTERN: Regex = Regex::new(
+        r"\b(?:\d{4}[- ]\d{6}[- ]\d{4,5}|\d{4}[- ]\d{4}[- ]\d{4}[- ]\d{4}(?:[- ]\d{3})?|\d{4}[- ]\d{4}[- ]\d{5}|\d{12,19})\b"
+    ).unwrap();
     static ref IP_ADDRESS_PATTERN: Regex = Regex::new(r"\b\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}\b").unwrap();
-    static ref EMAIL_PATTERN: Regex = Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Z|a-z]{2,}\b").unwrap();
+    // Two to eight colon-separated groups, any of them empty where `::`
+    // compresses zeros, down to `::1`, optionally ending in a dotted IPv4
+    // address. The half boundaries keep `std::io` and similar from matching,
+    // and a bare `::` is dropped as the unspecified address
+    static ref IPV6_ADDRESS_PATTERN: Regex = Regex::new(
+        r"(?i)\b{start-half}(?:[0-9a-f]{1,4}|:)(?::[0-9a-f]{0,4}){1,7}(?:\.\d{1,3}\.\d{1,3}\.\d{1,3})?\b{end-half}"
+    ).unwrap();
+    // Colon or dash separated octets, or Cisco's dotted groups of four
+    static ref MAC_ADDRESS_PATTERN: Regex = Regex::new(
+        r"(?i)\b[0-9a-f]{2}(?::[0-9a-f]{2}){5}\b|\b[0-9a-f]{2}(?:-[0-9a-f]{2}){5}\b|\b[0-9a-f]{4}\.[0-9a-f]{4}\.[0-9a-f]{4}\b"
+    ).unwrap();
+    static ref HOSTNAME_PATTERN: Regex = Regex::new(r"(?i)\b[a-z0-9-]{1,63}(?:\.[a-z0-9-]{1,63}){1,7}\b").unwrap();
+    // Bounded by the RFC 5321 local part and domain limits so a streaming
+    // window can hold back enough text to settle every match
+    static ref EMAIL_PATTERN: Regex = Regex::new(r"\b[A-Za-z0-9._%+-]{1,64}@[A-Za-z0-9.-]{1,253}\.[A-Za-z]{2,63}\b").unwrap();
+
+    // National ID numbers, in their usual written forms
+    static ref UK_NINO_PATTERN: Regex = Regex::new(r"\b[A-Z]{2} ?\d{2} ?\d{2} ?\d{2} ?[A-D]\b").unwrap();
+    static ref CA_SIN_PATTERN: Regex = Regex::new(r"\b\d{3}[- ]?\d{3}[- ]?\d{3}\b").unwrap();
+    static ref FR_INSEE_PATTERN: Regex = Regex::new(
+        r"\b[12] ?\d{2} ?(?:0[1-9]|1[0-2]|[2-9]\d) ?(?:\d{2}|2[AB]) ?\d{3} ?\d{3} ?\d{2}\b"
+    ).unwrap();
+    static ref DE_STEUER_ID_PATTERN: Regex = Regex::new(r"\b[1-9]\d ?\d{3} ?\d{3} ?\d{3}\b").unwrap();
+    static ref IT_CODICE_FISCALE_PATTERN: Regex = Regex::new(
+        r"\b[A-Z]{6}[\dLMNP-V]{2}[A-EHLMPRST][\dLMNP-V]{2}[A-Z][\dLMNP-V]{3}[A-Z]\b"
+    ).unwrap();
+    static ref ES_DNI_PATTERN: Regex = Regex::new(r"\b\d{8}-?[A-Z]\b").unwrap();
+    static ref ES_NIE_PATTERN: Regex = Regex::new(r"\b[XYZ]-?\d{7}-?[A-Z]\b").unwrap();
+    static ref NL_BSN_PATTERN: Regex = Regex::new(r"\b\d{4}\.?\d{2}\.?\d{3}\b").unwrap();
+    static ref IN_AADHAAR_PATTERN: Regex = Regex::new(r"\b[2-9]\d{3}[- ]?\d{4}[- ]?\d{4}\b").unwrap();
+    static ref IN_PAN_PATTERN: Regex = Regex::new(r"\b[A-Z]{5}\d{4}[A-Z]\b").unwrap();
+    static ref BR_CPF_PATTERN: Regex = Regex::new(r"\b\d{3}\.?\d{3}\.?\d{3}-?\d{2}\b").unwrap();
+    static ref BR_CNPJ_PATTERN: Regex = Regex::new(r"\b\d{2}\.?\d{3}\.?\d{3}/?\d{4}-?\d{2}\b").unwrap();
+
+    // Bank identifiers; IBANs compact or printed in groups of four, at most
+    // 34 characters and 8 spaces either way
+    static ref IBAN_PATTERN: Regex = Regex::new(
+        r"\b[A-Z]{2}[0-9]{2}(?:[A-Z0-9]{11,30}|(?: [A-Z0-9]{4}){7}(?: [A-Z0-9]{1,2})?|(?: [A-Z0-9]{4}){2,6}(?: [A-Z0-9]{1,3})?)\b"
+    ).unwrap();
+    static ref SWIFT_BIC_PATTERN: Regex = Regex::new(r"\b[A-Z]{6}[A-Z0-9]{2}(?:[A-Z0-9]{3})?\b").unwrap();
+    static ref ABA_ROUTING_PATTERN: Regex = Regex::new(r"\b\d{9}\b").unwrap();
+    static ref BANK_ACCOUNT_PATTERN: Regex = Regex::new(r"\b\d{6,17}\b").unwrap();
 }
 
-pub fn detect_pii_patterns(text: &str) -> Vec<PIIPattern> {
-    let mut patterns = Vec::new();
-    
-    // Phone numbers
-    for cap in PHONE_PATTERN.find_iter(text) {
-        patterns.push(PIIPattern {
+/// Options for the built-in PII detectors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PiiConfig {
+    /// Report card numbers that fail the Luhn check, at low confidence,
+    /// instead of dropping them
+    pub report_luhn_failures: bool,
+    /// Domain suffixes of internal hosts; names under them are reported as
+    /// `hostname`
+    pub internal_domains: Vec<String>,
+    /// Bytes searched on each side of a match for context keywords
+    pub keyword_window: usize,
+    /// Confidence added by a supporting keyword near a match
+    pub keyword_boost: f64,
+    /// Confidence removed by a negating keyword near a match
+    pub keyword_penalty: f64,
+    /// Keywords added to the built-in ones, by PII type
+    pub keywords: HashMap<String, ContextKeywords>,
+}
+
+impl Default for PiiConfig {
+    fn default() -> Self {
+        Self {
+            report_luhn_failures: false,
+            internal_domains: ["internal", "local", "lan", "corp", "intranet", "home.arpa"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            keyword_window: DEFAULT_KEYWORD_WINDOW,
+            keyword_boost: 0.1,
+            keyword_penalty: 0.3,
+            keywords: HashMap::new(),
+        }
+    }
+}
+
+impl PiiConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, suffix) in self.internal_domains.iter().enumerate() {
+            if !is_domain_suffix(suffix) {
+                return Err(format!(
+                    "pii.internal_domains[{}] must be a domain suffix such as corp.example.com, got '{}'",
+                    i, suffix
+                ));
+            }
+        }
+        if self.keyword_window > MAX_KEYWORD_WINDOW {
+            return Err(format!(
+                "pii.keyword_window must be at most {}, got {}",
+                MAX_KEYWORD_WINDOW, self.keyword_window
+            ));
+        }
+        for (name, value) in [("keyword_boost", self.keyword_boost), ("keyword_penalty", self.keyword_penalty)] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(format!("pii.{} must be between 0 and 1, got {}", name, value));
+            }
+        }
+        let mut types: Vec<&String> = self.keywords.keys().collect();
+        types.sort();
+        for type_ in types {
+            if !is_keyword_type(type_) {
+                return Err(format!("pii.keywords has unknown PII type '{}'", type_));
+            }
+            self.keywords[type_].validate(&format!("pii.keywords.{}", type_))?;
+        }
+        Ok(())
+    }
+}
+
+/// Default bytes on each side of a match searched for keywords such as "SSN"
+pub const DEFAULT_KEYWORD_WINDOW: usize = 32;
+
+/// Widest keyword window; streaming holds back this much text after a match
+pub const MAX_KEYWORD_WINDOW: usize = 256;
+
+/// Bytes before a match read by validators, such as the IPv4-mapped check
+const PREFIX_BYTES: usize = 16;
+
+/// Highest confidence a supporting keyword can raise a match to
+const MAX_KEYWORD_CONFIDENCE: f64 = 0.99;
+
+/// A regex match being assessed
+struct Candidate<'a> {
+    /// The matched text
+    text: &'a str,
+    /// The `PREFIX_BYTES` before the match
+    before: &'a str,
+    /// Context keywords within the keyword window
+    keywords: &'a KeywordMatches,
+}
+
+/// Score of a validated match, with anything validation learned about it
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Assessment {
+    confidence: f64,
+    card_network: Option<&'static str>,
+    classification: Option<&'static str>,
+    severity: Option<&'static str>,
+}
+
+impl Assessment {
+    fn new(confidence: f64) -> Self {
+        Self { confidence, card_network: None, classification: None, severity: None }
+    }
+
+    fn classified(confidence: f64, classification: &'static str) -> Self {
+        Self { classification: Some(classification), ..Self::new(confidence) }
+    }
+}
+
+/// A PII pattern and how to score its matches.
+///
+/// `assess` returns `None` for matches that fail validation. With
+/// `standalone`, a match right after another digit group, like the tail of a
+/// grouped card number, is part of a longer number and skipped. With
+/// `keyword_scored`, `assess` already weighs supporting keywords, so they add
+/// no boost on top.
+struct PiiDetector {
+    type_: String,
+    regex: Regex,
+    max_match_bytes: usize,
+    standalone: bool,
+    keyword_scored: bool,
+    assess: AssessFn,
+}
+
+type AssessFn = Box<dyn Fn(&Candidate, &PiiConfig) -> Option<Assessment>>;
+
+fn detectors() -> Vec<PiiDetector> {
+    vec![
+        PiiDetector {
             type_: "phone".to_string(),
-            pattern: cap.as_str().to_string(),
-            position: cap.start(),
-            confidence: calculate_phone_confidence(cap.as_str()),
-        });
-    }
-    
-    // SSN
-    for cap in SSN_PATTERN.find_iter(text) {
-        patterns.push(PIIPattern {
+            regex: PHONE_PATTERN.clone(),
+            max_match_bytes: 2 + 3 + 7 + 6 * 8,
+            standalone: true,
+            keyword_scored: false,
+            assess: Box::new(|c, _| calculate_phone_confidence(c.text).map(Assessment::new)),
+        },
+        PiiDetector {
             type_: "ssn".to_string(),
-            pattern: cap.as_str().to_string(),
-            position: cap.start(),
-            confidence: 0.95, // High confidence for SSN format
-        });
-    }
-    
-    // Credit cards
-    for cap in CREDIT_CARD_PATTERN.find_iter(text) {
-        patterns.push(PIIPattern {
+            regex: SSN_PATTERN.clone(),
+            max_match_bytes: 11,
+            standalone: true,
+            keyword_scored: true,
+            assess: Box::new(|c, _| calculate_ssn_confidence(c.text, c.keywords.supported()).map(Assessment::new)),
+        },
+        PiiDetector {
             type_: "credit_card".to_string(),
-            pattern: cap.as_str().to_string(),
-            position: cap.start(),
-            confidence: calculate_credit_card_confidence(cap.as_str()),
-        });
-    }
-    
-    // IP addresses
-    for cap in IP_ADDRESS_PATTERN.find_iter(text) {
-        if is_valid_ip_address(cap.as_str()) {
-            patterns.push(PIIPattern {
-                type_: "ip_address".to_string(),
-                pattern: cap.as_str().to_string(),
-                position: cap.start(),
-                confidence: 0.9,
-            });
-        }
-    }
-    
-    // Email addresses
-    for cap in EMAIL_PATTERN.find_iter(text) {
-        patterns.push(PIIPattern {
+            regex: CREDIT_CARD_PATTERN.clone(),
+            max_match_bytes: 19 + 4,
+            standalone: false,
+            keyword_scored: false,
+            assess: Box::new(|c, config| assess_credit_card(c.text, config)),
+        },
+        PiiDetector {
+            type_: "ip_address".to_string(),
+            regex: IP_ADDRESS_PATTERN.clone(),
+            max_match_bytes: 15,
+            standalone: false,
+            keyword_scored: false,
+            assess: Box::new(|c, _| ipv4_classification(c.text, c.before).map(|class| Assessment::classified(0.9, class))),
+        },
+        PiiDetector {
+            type_: "ip_address".to_string(),
+            regex: IPV6_ADDRESS_PATTERN.clone(),
+            max_match_bytes: 4 + 7 * 5 + 12,
+            standalone: false,
+            keyword_scored: false,
+            assess: Box::new(|c, _| ipv6_classification(c.text).map(|class| Assessment::classified(0.9, class))),
+        },
+        PiiDetector {
+            type_: "mac_address".to_string(),
+            regex: MAC_ADDRESS_PATTERN.clone(),
+            max_match_bytes: 17,
+            standalone: false,
+            keyword_scored: false,
+            assess: Box::new(|c, _| mac_address_confidence(c.text).map(Assessment::new)),
+        },
+        PiiDetector {
+            type_: "hostname".to_string(),
+            regex: HOSTNAME_PATTERN.clone(),
+            max_match_bytes: 8 * 63 + 7,
+            standalone: false,
+            keyword_scored: false,
+            assess: Box::new(|c, config| {
+                is_internal_hostname(c.text, c.before, &config.internal_domains).then(|| Assessment::classified(0.8, "private"))
+            }),
+        },
+        PiiDetector {
             type_: "email".to_string(),
-            pattern: cap.as_str().to_string(),
-            position: cap.start(),
-            confidence: 0.85,
-        });
+            regex: EMAIL_PATTERN.clone(),
+            max_match_bytes: 64 + 1 + 253 + 1 + 63,
+            standalone: false,
+            keyword_scored: false,
+            assess: Box::new(|_, _| Some(Assessment::new(0.85))),
+        },
+        id_detector("uk_nino", &UK_NINO_PATTERN, 13, uk_nino_confidence),
+        contextual_detector("ca_sin", &CA_SIN_PATTERN, 11, ca_sin_confidence),
+        id_detector("fr_insee", &FR_INSEE_PATTERN, 21, fr_insee_confidence),
+        contextual_detector("de_steuer_id", &DE_STEUER_ID_PATTERN, 14, de_steuer_id_confidence),
+        id_detector("it_codice_fiscale", &IT_CODICE_FISCALE_PATTERN, 16, it_codice_fiscale_confidence),
+        id_detector("es_dni", &ES_DNI_PATTERN, 10, es_dni_confidence),
+        id_detector("es_nie", &ES_NIE_PATTERN, 11, es_nie_confidence),
+        contextual_detector("nl_bsn", &NL_BSN_PATTERN, 11, nl_bsn_confidence),
+        id_detector("in_aadhaar", &IN_AADHAAR_PATTERN, 14, in_aadhaar_confidence),
+        id_detector("in_pan", &IN_PAN_PATTERN, 10, in_pan_confidence),
+        id_detector("br_cpf", &BR_CPF_PATTERN, 14, br_cpf_confidence),
+        id_detector("br_cnpj", &BR_CNPJ_PATTERN, 18, br_cnpj_confidence),
+        id_detector("iban", &IBAN_PATTERN, 34 + 8, iban_confidence),
+        contextual_detector("swift_bic", &SWIFT_BIC_PATTERN, 11, swift_bic_confidence),
+        contextual_detector("aba_routing", &ABA_ROUTING_PATTERN, 9, aba_routing_confidence),
+        contextual_detector("bank_account", &BANK_ACCOUNT_PATTERN, 17, bank_account_confidence),
+    ]
+}
+
+/// Detector scored by `confidence` from the match and whether a supporting
+/// keyword is nearby
+fn contextual_detector(
+    type_: &str,
+    regex: &Regex,
+    max_match_bytes: usize,
+    confidence: fn(&str, bool) -> Option<f64>,
+) -> PiiDetector {
+    PiiDetector {
+        type_: type_.to_string(),
+        regex: regex.clone(),
+        max_match_bytes,
+        standalone: true,
+        keyword_scored: true,
+        assess: Box::new(move |c, _| confidence(c.text, c.keywords.supported()).map(Assessment::new)),
     }
-    
-    patterns
 }
 
-fn calculate_phone_confidence(phone: &str) -> f64 {
-    // Remove non-digits
-    let digits: String = phone.chars().filter(|c| c.is_digit(10)).collect();
-    
-    if digits.len() == 10 {
-        // Check for common area codes and patterns
-        if phone.contains('-') || phone.contains('.') {
-            0.9
-        } else {
-            0.8
-        }
-    } else {
-        0.6
+/// Detector for a national ID or IBAN scored by `confidence` from the match
+/// alone
+fn id_detector(
+    type_: &str,
+    regex: &Regex,
+    max_match_bytes: usize,
+    confidence: fn(&str) -> Option<f64>,
+) -> PiiDetector {
+    PiiDetector {
+        type_: type_.to_string(),
+        regex: regex.clone(),
+        max_match_bytes,
+        standalone: true,
+        keyword_scored: false,
+        assess: Box::new(move |c, _| confidence(c.text).map(Assessment::new)),
     }
 }
 
-fn calculate_credit_card_confidence(card: &str) -> f64 {
-    // Remove non-digits
-    let digits: String = card.chars().filter(|c| c.is_digit(10)).collect();
-    
-    if digits.len() == 16 {
-        // Basic Luhn algorithm check
-        if luhn_check(&digits) {
-            0.95
+/// Detector for a user-defined pattern, scored at its configured confidence
+/// when its validator, if any, accepts the match
+fn custom_detector(pattern: &CustomPiiPattern, path: &str) -> Result<PiiDetector, String> {
+    let (regex, max_match_bytes) = pattern.compile(path)?;
+    let validator = pattern.validator.clone();
+    let assessment = Assessment { severity: Some(pattern.severity.as_str()), ..Assessment::new(pattern.confidence) };
+    Ok(PiiDetector {
+        type_: pattern.name.clone(),
+        regex,
+        max_match_bytes,
+        standalone: false,
+        keyword_scored: false,
+        assess: Box::new(move |c, _| validator.as_ref().is_none_or(|v| v.accepts(c.text)).then_some(assessment)),
+    })
+}
+
+/// Numbering plan of a country: its calling code and the valid lengths of the
+/// national significant number (the digits after the calling code)
+struct PhonePlan {
+    calling_code: &'static str,
+    lengths: (usize, usize),
+    /// Dialled with a leading 0 within the country, dropped after the calling code
+    trunk_zero: bool,
+}
+
+const PHONE_PLANS: &[PhonePlan] = &[
+    PhonePlan { calling_code: "1", lengths: (10, 10), trunk_zero: false },   // US, CA (NANP)
+    PhonePlan { calling_code: "44", lengths: (9, 10), trunk_zero: true },    // GB
+    PhonePlan { calling_code: "49", lengths: (7, 12), trunk_zero: true },    // DE
+    PhonePlan { calling_code: "33", lengths: (9, 9), trunk_zero: true },     // FR
+    PhonePlan { calling_code: "39", lengths: (6, 11), trunk_zero: false },   // IT, keeps its 0
+    PhonePlan { calling_code: "34", lengths: (9, 9), trunk_zero: false },    // ES
+    PhonePlan { calling_code: "31", lengths: (9, 9), trunk_zero: true },     // NL
+    PhonePlan { calling_code: "32", lengths: (8, 9), trunk_zero: true },     // BE
+    PhonePlan { calling_code: "41", lengths: (9, 9), trunk_zero: true },     // CH
+    PhonePlan { calling_code: "46", lengths: (7, 10), trunk_zero: true },    // SE
+    PhonePlan { calling_code: "48", lengths: (9, 9), trunk_zero: false },    // PL
+    PhonePlan { calling_code: "353", lengths: (7, 9), trunk_zero: true },    // IE
+    PhonePlan { calling_code: "61", lengths: (9, 9), trunk_zero: true },     // AU
+    PhonePlan { calling_code: "64", lengths: (8, 10), trunk_zero: true },    // NZ
+    PhonePlan { calling_code: "81", lengths: (9, 10), trunk_zero: true },    // JP
+    PhonePlan { calling_code: "82", lengths: (8, 10), trunk_zero: true },    // KR
+    PhonePlan { calling_code: "86", lengths: (10, 11), trunk_zero: true },   // CN
+    PhonePlan { calling_code: "91", lengths: (10, 10), trunk_zero: true },   // IN
+    PhonePlan { calling_code: "65", lengths: (8, 8), trunk_zero: false },    // SG
+    PhonePlan { calling_code: "852", lengths: (8, 8), trunk_zero: false },   // HK
+    PhonePlan { calling_code: "55", lengths: (10, 11), trunk_zero: true },   // BR
+    PhonePlan { calling_code: "52", lengths: (10, 10), trunk_zero: false },  // MX
+];
+
+impl PhonePlan {
+    fn accepts(&self, national_number: &str) -> bool {
+        (self.lengths.0..=self.lengths.1).contains(&national_number.len())
+    }
+}
+
+/// Calling codes are prefix-free, so at most one plan matches
+fn phone_plan(digits: &str) -> Option<&'static PhonePlan> {
+    PHONE_PLANS.iter().find(|plan| digits.starts_with(plan.calling_code))
+}
+
+/// A card network, identified by the IIN (leading digits) of its numbers
+struct CardNetwork {
+    name: &'static str,
+    /// Inclusive prefix ranges; both ends have the prefix's digit count
+    iin_ranges: &'static [(u32, u32)],
+    lengths: &'static [usize],
+}
+
+/// Checked in order, so narrower ranges come before the broad ones they sit in
+/// (Discover's 622126-622925 before UnionPay's 62)
+const CARD_NETWORKS: &[CardNetwork] = &[
+    CardNetwork { name: "amex", iin_ranges: &[(34, 34), (37, 37)], lengths: &[15] },
+    CardNetwork { name: "diners_club", iin_ranges: &[(300, 305), (36, 36), (38, 39)], lengths: &[14, 15, 16, 17, 18, 19] },
+    CardNetwork { name: "jcb", iin_ranges: &[(3528, 3589)], lengths: &[16, 17, 18, 19] },
+    CardNetwork { name: "mir", iin_ranges: &[(2200, 2204)], lengths: &[16, 17, 18, 19] },
+    CardNetwork { name: "mastercard", iin_ranges: &[(2221, 2720), (51, 55)], lengths: &[16] },
+    CardNetwork {
+        name: "maestro",
+        iin_ranges: &[(5018, 5018), (5020, 5020), (5038, 5038), (5893, 5893), (6304, 6304), (6759, 6759), (6761, 6763)],
+        lengths: &[12, 13, 14, 15, 16, 17, 18, 19],
+    },
+    CardNetwork {
+        name: "discover",
+        iin_ranges: &[(6011, 6011), (622126, 622925), (644, 649), (65, 65)],
+        lengths: &[16, 17, 18, 19],
+    },
+    CardNetwork { name: "unionpay", iin_ranges: &[(62, 62)], lengths: &[16, 17, 18, 19] },
+    CardNetwork { name: "visa", iin_ranges: &[(4, 4)], lengths: &[13, 16, 19] },
+];
+
+impl CardNetwork {
+    fn matches(&self, digits: &str) -> bool {
+        self.lengths.contains(&digits.len())
+            && self.iin_ranges.iter().any(|&(low, high)| {
+                let width = low.to_string().len();
+                digits[..width].parse::<u32>().is_ok_and(|iin| (low..=high).contains(&iin))
+            })
+    }
+}
+
+/// Network whose IIN ranges and lengths fit `digits`
+fn card_network(digits: &str) -> Option<&'static str> {
+    CARD_NETWORKS.iter().find(|network| network.matches(digits)).map(|network| network.name)
+}
+
+/// PII matcher shared by one-shot and streaming analysis.
+///
+/// Each detector keeps its own resume cursor into a `ChunkWindow`, so scanning
+/// chunk by chunk reports exactly the matches a single pass would. Matches of
+/// different detectors that overlap are resolved to non-overlapping findings
+/// (see `resolve_overlaps`); a group still open at the limit, which a later
+/// match could join, is held back until it closes.
+pub struct PiiScanner {
+    detectors: Vec<PiiDetector>,
+    /// Context keywords of each detector's type
+    keywords: Vec<KeywordMatcher>,
+    cursors: Vec<usize>,
+    /// Matches awaiting overlap resolution, in position order
+    pending: Vec<PIIPattern>,
+    config: PiiConfig,
+}
+
+impl std::fmt::Debug for PiiScanner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PiiScanner")
+            .field("types", &self.detectors.iter().map(|d| d.type_.as_str()).collect::<Vec<_>>())
+            .field("cursors", &self.cursors)
+            .field("pending", &self.pending.len())
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl Default for PiiScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PiiScanner {
+    pub fn new() -> Self {
+        Self::with_config(PiiConfig::default())
+    }
+
+    pub fn with_config(config: PiiConfig) -> Self {
+        let detectors = detectors();
+        let keywords = detectors
+            .iter()
+            .map(|d| KeywordMatcher::new(&d.type_, config.keywords.get(&d.type_)))
+            .collect();
+        let cursors = vec![0; detectors.len()];
+        Self { detectors, keywords, cursors, pending: Vec::new(), config }
+    }
+
+    /// Scanner with the built-in detectors followed by the enabled custom ones,
+    /// whose regexes are compiled here
+    pub fn with_custom_patterns(config: PiiConfig, custom: &[CustomPiiPattern]) -> Result<Self, String> {
+        let mut scanner = Self::with_config(config);
+        for (i, pattern) in custom.iter().enumerate().filter(|(_, p)| p.enabled) {
+            scanner.detectors.push(custom_detector(pattern, &format!("custom_pii_patterns[{}]", i))?);
+            scanner.keywords.push(KeywordMatcher::new(&pattern.name, Some(&pattern.keywords)));
+            scanner.cursors.push(0);
+        }
+        Ok(scanner)
+    }
+
+    /// Widest match any detector can report, in bytes
+    pub fn max_match_bytes(&self) -> usize {
+        self.detectors.iter().map(|d| d.max_match_bytes).max().unwrap_or(0)
+    }
+
+    /// Bytes of context needed on each side of a match
+    pub fn context_radius(&self) -> usize {
+        self.config.keyword_window.max(PREFIX_BYTES)
+    }
+
+    /// Earliest position any detector still has to resume from
+    pub fn oldest_cursor(&self) -> Option<usize> {
+        self.cursors.iter().copied().min()
+    }
+
+    /// Position of the first match held back for overlap resolution
+    pub fn pending_start(&self) -> Option<usize> {
+        self.pending.first().map(|p| p.position)
+    }
+
+    /// Report findings starting before `limit`, ordered by position and not
+    /// overlapping each other
+    pub fn scan(&mut self, window: &ChunkWindow, limit: usize) -> Vec<PIIPattern> {
+        let mut matches = std::mem::take(&mut self.pending);
+        matches.extend(self.find_matches(window, limit));
+        matches.sort_by_key(|p| p.position);
+
+        // Overlapping matches form groups; a group that ends after `limit`
+        // may still be joined by a match starting there
+        let mut resolved = Vec::new();
+        let mut group: Vec<PIIPattern> = Vec::new();
+        let mut group_end = 0;
+        for pattern in matches {
+            if !group.is_empty() && pattern.position >= group_end {
+                resolved.extend(resolve_overlaps(std::mem::take(&mut group)));
+            }
+            group_end = if group.is_empty() { pattern_end(&pattern) } else { group_end.max(pattern_end(&pattern)) };
+            group.push(pattern);
+        }
+        if group_end <= limit {
+            resolved.extend(resolve_overlaps(group));
         } else {
-            0.7
+            self.pending = group;
         }
-    } else {
-        0.5
+        resolved
     }
-}
 
-fn luhn_check(digits: &str) -> bool {
-    let mut sum = 0;
-    let mut alternate = false;
-    
-    for digit in digits.chars().rev() {
-        if let Some(mut num) = digit.to_digit(10) {
-            if alternate {
-                num *= 2;
-                if num > 9 {
-                    num = (num % 10) + 1;
+    /// Matches of every detector starting before `limit`, in position order
+    fn find_matches(&mut self, window: &ChunkWindow, limit: usize) -> Vec<PIIPattern> {
+        let mut found = Vec::new();
+        for (index, detector) in self.detectors.iter().enumerate() {
+            for (start, end) in window.find_settled(&detector.regex, &mut self.cursors[index], limit) {
+                if detector.standalone && continues_number(window, start) {
+                    continue;
+                }
+                let pattern = window.slice(start, end);
+                let radius = self.config.keyword_window;
+                let keywords = self.keywords[index].find(
+                    window.slice(start.saturating_sub(radius), start),
+                    window.slice(end, end + radius),
+                );
+                let candidate = Candidate {
+                    text: pattern,
+                    before: window.slice(start.saturating_sub(PREFIX_BYTES), start),
+                    keywords: &keywords,
+                };
+                let Some(mut assessment) = (detector.assess)(&candidate, &self.config) else {
+                    continue;
+                };
+                assessment.confidence = self.keyword_confidence(detector, assessment.confidence, &keywords);
+                if assessment.confidence > 0.0 {
+                    found.push((start, end, index, pattern.to_string(), assessment, keywords));
                 }
             }
-            sum += num;
-            alternate = !alternate;
         }
+        found.sort_by_key(|&(start, _, index, _, _, _)| (start, index));
+
+        let mut locator = window.locator();
+        found
+            .into_iter()
+            .map(|(start, end, index, pattern, assessment, keywords)| PIIPattern {
+                id: String::new(),
+                type_: self.detectors[index].type_.clone(),
+                pattern,
+                position: start,
+                confidence: assessment.confidence,
+                card_network: assessment.card_network.map(str::to_string),
+                classification: assessment.classification.map(str::to_string),
+                severity: assessment.severity.map(str::to_string),
+                supporting_keywords: keywords.supporting,
+                negating_keywords: keywords.negating,
+                alternatives: Vec::new(),
+                location: TextLocation::from_span(&mut locator, window.relative(start), window.relative(end)),
+                recommended_action: Decision::Allow,
+            })
+            .collect()
     }
-    
-    sum % 10 == 0
+
+    /// Confidence raised by a supporting keyword, unless the detector already
+    /// weighed it, and lowered by a negating one
+    fn keyword_confidence(&self, detector: &PiiDetector, confidence: f64, keywords: &KeywordMatches) -> f64 {
+        let mut adjusted = confidence;
+        if keywords.supported() && !detector.keyword_scored {
+            adjusted = (adjusted + self.config.keyword_boost).min(MAX_KEYWORD_CONFIDENCE).max(confidence);
+        }
+        if !keywords.negating.is_empty() {
+            adjusted -= self.config.keyword_penalty;
+        }
+        // Drop the float noise of the sums, keeping configured precision
+        (adjusted.max(0.0) * 1e6).round() / 1e6
+    }
+}
+
+fn pattern_end(pattern: &PIIPattern) -> usize {
+    pattern.position + pattern.location.byte_length
+}
+
+/// How much a match of `type_` says beyond its shape: validated identifiers
+/// first, then phone numbers and hostnames, then keyword-gated bank accounts
+fn specificity(type_: &str) -> u8 {
+    match type_ {
+        "bank_account" => 0,
+        "phone" | "hostname" => 1,
+        _ => 2,
+    }
+}
+
+/// Keep the best of a group of overlapping matches and every other match not
+/// overlapping a kept one. Matches rank by confidence, then specificity, then
+/// length; a dropped match is recorded as an alternative of the kept match
+/// that outranked it.
+fn resolve_overlaps(mut group: Vec<PIIPattern>) -> Vec<PIIPattern> {
+    if group.len() == 1 {
+        return group;
+    }
+    // Stable, so equal matches keep position and detector order
+    group.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| specificity(&b.type_).cmp(&specificity(&a.type_)))
+            .then_with(|| b.location.byte_length.cmp(&a.location.byte_length))
+    });
+
+    let mut kept: Vec<PIIPattern> = Vec::new();
+    for pattern in group {
+        let overlapping = kept
+            .iter_mut()
+            .find(|k| k.position < pattern_end(&pattern) && pattern.position < pattern_end(k));
+        match overlapping {
+            Some(winner) => winner.alternatives.push(PIIAlternative {
+                type_: pattern.type_,
+                pattern: pattern.pattern,
+                position: pattern.position,
+                confidence: pattern.confidence,
+            }),
+            None => kept.push(pattern),
+        }
+    }
+    kept.sort_by_key(|p| p.position);
+    kept
 }
 
-fn is_valid_ip_address(ip: &str) -> bool {
-    ip.split('.')
-        .all(|octet| {
-            octet.parse::<u8>().is_ok()
-        })
+/// Whether `start` directly follows a digit and one separator
+fn continues_number(window: &ChunkWindow, start: usize) -> bool {
+    match window.char_before(start) {
+        Some(separator @ (' ' | '-' | '.')) => window
+            .char_before(start - separator.len_utf8())
+            .is_some_and(|c| c.is_ascii_digit()),
+        _ => false,
+    }
+}
+
+pub fn detect_pii_patterns(text: &str) -> Vec<PIIPattern> {
+    let mut window = ChunkWindow::new();
+    window.push(text);
+    PiiScanner::new().scan(&window, window.end())
+}
+
+/// Confidence from the numbering plan the number fits, `None` when it fits none.
+///
+/// International numbers are checked against their country's lengths, national
+/// numbers with a trunk 0 against every country dialled that way.
+fn calculate_phone_confidence(phone: &str) -> Option<f64> {
+    if let Some(international) = phone.strip_prefix('+').or_else(|| phone.strip_prefix("00")) {
+        // "+44 (0)20 ..." repeats the trunk 0 for national callers
+        let digits: String = international.replace("(0)", "").chars().filter(|c| c.is_ascii_digit()).collect();
+        return match phone_plan(&digits) {
+            Some(plan) => plan.accepts(&digits[plan.calling_code.len()..]).then_some(0.95),
+            // E.164 numbers are at most 15 digits
+            None => (8..=15).contains(&digits.len()).then_some(0.6),
+        };
+    }
+
+    // Remove non-digits
+    let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+
+    // North American area codes never start with 0, which is a trunk prefix elsewhere
+    if NANP_PHONE_PATTERN.is_match(phone) && !digits.starts_with('0') {
+        // Separators make a number more likely than a bare run of digits
+        return Some(if phone.contains(['-', '.', ' ', '(']) { 0.9 } else { 0.8 });
+    }
+
+    if DATE_LIKE_PATTERN.is_match(phone) {
+        return None;
+    }
+    let national_number = digits.strip_prefix('0')?;
+    PHONE_PLANS
+        .iter()
+        .any(|plan| plan.trunk_zero && plan.accepts(national_number))
+        .then_some(0.7)
+}
+
+/// Whether an SSN follows the SSA's issuing rules: no 000, 666 or 900-999
+/// area, no 00 group, no 0000 serial, and none of the numbers voided after
+/// appearing in advertising
+fn is_valid_ssn(digits: &str) -> bool {
+    let (area, rest) = digits.split_at(3);
+    let (group, serial) = rest.split_at(2);
+    area != "000"
+        && area != "666"
+        && !area.starts_with('9')
+        && group != "00"
+        && serial != "0000"
+        && !["078051120", "219099999"].contains(&digits)
+}
+
+/// Dashed SSNs are reported on their own; spaced and undashed ones, which
+/// look like many other numbers, only next to an SSN keyword. A keyword
+/// raises the confidence of every form.
+fn calculate_ssn_confidence(ssn: &str, keyword: bool) -> Option<f64> {
+    let digits: String = ssn.chars().filter(|c| c.is_ascii_digit()).collect();
+    if !is_valid_ssn(&digits) {
+        return None;
+    }
+
+    match (ssn.contains('-'), ssn.contains(' '), keyword) {
+        (true, _, true) => Some(0.95),
+        (true, _, false) => Some(0.85),
+        (_, true, true) => Some(0.9),
+        (_, false, true) => Some(0.85),
+        (_, _, false) => None,
+    }
+}
+
+/// Numbers outside every network's IIN ranges and lengths are not cards;
+/// Luhn failures are dropped unless `report_luhn_failures` is set
+fn assess_credit_card(card: &str, config: &PiiConfig) -> Option<Assessment> {
+    // Remove non-digits
+    let digits: String = card.chars().filter(|c| c.is_ascii_digit()).collect();
+    let network = card_network(&digits)?;
+
+    let confidence = if luhn_check(&digits) {
+        0.95
+    } else if config.report_luhn_failures {
+        0.5
+    } else {
+        return None;
+    };
+    Some(Assessment { card_network: Some(network), ..Assessment::new(confidence) })
 }
 
 #[cfg(test)]
@@ -155,12 +780,13 @@ mod tests {
 
     #[test]
     fn test_credit_card_detection() {
-        let text = "Card: 1234-5678-9012-3456";
+        let text = "Card: 4532-0151-1283-0366";
         let patterns = detect_pii_patterns(text);
         
         assert_eq!(patterns.len(), 1);
         assert_eq!(patterns[0].type_, "credit_card");
-        assert_eq!(patterns[0].pattern, "1234-5678-9012-3456");
+        assert_eq!(patterns[0].pattern, "4532-0151-1283-0366");
+        assert_eq!(patterns[0].card_network.as_deref(), Some("visa"));
     }
 
     #[test]
@@ -171,6 +797,78 @@ mod tests {
         assert_eq!(patterns.len(), 1);
         assert_eq!(patterns[0].type_, "ip_address");
         assert_eq!(patterns[0].pattern, "192.168.1.1");
+        assert_eq!(patterns[0].classification.as_deref(), Some("private"));
+    }
+
+    #[test]
+    fn test_ipv6_address_detection() {
+        let text = "hosts [2001:db8::1]:443, fe80::1%eth0 and ::ffff:8.8.8.8; see std::io::Error at 10:30:00";
+        let patterns = detect_pii_patterns(text);
+
+        let found: Vec<(&str, &str, Option<&str>)> = patterns
+            .iter()
+            .map(|p| (p.type_.as_str(), p.pattern.as_str(), p.classification.as_deref()))
+            .collect();
+        assert_eq!(found, vec![
+            ("ip_address", "2001:db8::1", Some("documentation")),
+            ("ip_address", "fe80::1", Some("link_local")),
+            ("ip_address", "::ffff:8.8.8.8", Some("public")),
+        ]);
+    }
+
+    #[test]
+    fn test_short_compressed_ipv6_addresses() {
+        let cases = [
+            ("connect to ::1 on port 80", "::1", "loopback"),
+            ("gateway fe80::1 only", "fe80::1", "link_local"),
+        ];
+        for (text, address, class) in cases {
+            let patterns = detect_pii_patterns(text);
+            assert_eq!(patterns.len(), 1, "{:?}", text);
+            assert_eq!(patterns[0].type_, "ip_address");
+            assert_eq!(patterns[0].pattern, address);
+            assert_eq!(patterns[0].classification.as_deref(), Some(class));
+        }
+
+        for text in ["map :: (a -> b) -> [a] -> [b]", "x ::= y", "bind to :: for all interfaces"] {
+            assert!(detect_pii_patterns(text).is_empty(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_mac_address_detection() {
+        let text = "eth0 00:1A:2B:3C:4D:5E, wlan 00-1a-2b-3c-4d-5f, cisco 001a.2b3c.4d60, none 00:00:00:00:00:00";
+        let patterns: Vec<String> = detect_pii_patterns(text)
+            .into_iter()
+            .filter(|p| p.type_ == "mac_address")
+            .map(|p| p.pattern)
+            .collect();
+        assert_eq!(patterns, vec!["00:1A:2B:3C:4D:5E", "00-1a-2b-3c-4d-5f", "001a.2b3c.4d60"]);
+    }
+
+    #[test]
+    fn test_internal_hostname_detection() {
+        let config = PiiConfig {
+            internal_domains: vec!["corp.example.com".to_string()],
+            ..PiiConfig::default()
+        };
+        let text = "deploy to build01.corp.example.com, mail ops@corp.example.com, docs at www.example.com";
+        let mut window = ChunkWindow::new();
+        window.push(text);
+        let patterns = PiiScanner::with_config(config).scan(&window, window.end());
+
+        let hosts: Vec<&PIIPattern> = patterns.iter().filter(|p| p.type_ == "hostname").collect();
+        assert_eq!(hosts.len(), 1);
+        assert_eq!(hosts[0].pattern, "build01.corp.example.com");
+        assert_eq!(hosts[0].classification.as_deref(), Some("private"));
+
+        assert_eq!(detect_pii_patterns("ssh db1.prod.internal")[0].type_, "hostname");
+
+        let invalid = PiiConfig { internal_domains: vec!["not a domain".to_string()], ..PiiConfig::default() };
+        assert_eq!(
+            invalid.validate().unwrap_err(),
+            "pii.internal_domains[0] must be a domain suffix such as corp.example.com, got 'not a domain'"
+        );
     }
 
     #[test]
@@ -200,17 +898,90 @@ mod tests {
             "123-456-7890",
             "123.456.7890",
             "1234567890",
+            "(123) 456-7890",
+            "123 456 7890",
         ];
         
         for format in formats {
             let patterns = detect_pii_patterns(format);
             assert!(!patterns.is_empty(), "Failed to detect: {}", format);
             assert_eq!(patterns[0].type_, "phone");
+            assert_eq!(patterns[0].pattern, format);
         }
-        
-        // Test format that should not be detected
-        let patterns = detect_pii_patterns("(123) 456-7890");
-        assert_eq!(patterns.len(), 0, "Should not detect parenthesized format");
+    }
+
+    #[test]
+    fn test_international_phone_numbers() {
+        let numbers = vec![
+            "+1 (415) 555-2671",   // US
+            "+44 20 7946 0958",    // GB
+            "+44 (0)20 7946 0958", // GB, trunk 0 repeated
+            "+49 30 1234567",      // DE
+            "+33 1 23 45 67 89",   // FR
+            "+39 06 1234 5678",    // IT
+            "+34 612 345 678",     // ES
+            "+31 20 123 4567",     // NL
+            "+353 1 234 5678",     // IE
+            "+61 2 9876 5432",     // AU
+            "+81 3-1234-5678",     // JP
+            "+86 138 0013 8000",   // CN
+            "+91 98765 43210",     // IN
+            "+65 6123 4567",       // SG
+            "0044 20 7946 0958",   // 00 international prefix
+            "+442079460958",       // E.164
+        ];
+
+        for number in numbers {
+            let patterns = detect_pii_patterns(number);
+            assert_eq!(patterns.len(), 1, "Failed to detect: {}", number);
+            assert_eq!(patterns[0].type_, "phone");
+            assert_eq!(patterns[0].pattern, number);
+            assert_eq!(patterns[0].confidence, 0.95, "Wrong confidence for {}", number);
+        }
+    }
+
+    #[test]
+    fn test_national_phone_numbers() {
+        let numbers = vec![
+            "020 7946 0958",  // GB
+            "030 1234567",    // DE
+            "01 23 45 67 89", // FR
+            "(02) 9876 5432", // AU
+            "03-1234-5678",   // JP
+        ];
+
+        for number in numbers {
+            let patterns = detect_pii_patterns(number);
+            assert_eq!(patterns.len(), 1, "Failed to detect: {}", number);
+            assert_eq!(patterns[0].pattern, number);
+            assert_eq!(patterns[0].confidence, 0.7);
+        }
+
+        // The tail of a grouped card number is not a national phone number
+        let patterns = detect_pii_patterns("4532 0151 1283 0366");
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].type_, "credit_card");
+    }
+
+    #[test]
+    fn test_phone_length_validation() {
+        let invalid = vec![
+            "+44 20 7946 09",    // Too short for GB
+            "+33 1 23 45 67 8",  // Too short for FR
+            "+1 415 555 26710",  // Too long for NANP
+            "01 23",             // No country has 3-digit numbers
+            "05-10-2023",        // A date
+        ];
+
+        for number in invalid {
+            let patterns = detect_pii_patterns(number);
+            assert!(patterns.is_empty(), "Should not detect: {}", number);
+        }
+
+        // Unknown calling codes only need a plausible E.164 length
+        let patterns = detect_pii_patterns("+999 1234 5678");
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].confidence, 0.6);
     }
 
     #[test]
@@ -234,7 +1005,47 @@ mod tests {
         
         assert_eq!(patterns.len(), 1);
         assert_eq!(patterns[0].type_, "ssn");
-        assert_eq!(patterns[0].confidence, 0.95);
+        assert_eq!(patterns[0].confidence, 0.85);
+    }
+
+    #[test]
+    fn test_ssn_keyword_context() {
+        let cases = vec![
+            ("SSN: 123-45-6789", "123-45-6789", 0.95),
+            ("Social Security Number 123 45 6789", "123 45 6789", 0.9),
+            ("ssn#123456789", "123456789", 0.85),
+            ("123456789 (soc. sec.)", "123456789", 0.85),
+        ];
+
+        for (text, ssn, confidence) in cases {
+            let patterns = detect_pii_patterns(text);
+            let found = patterns.iter().find(|p| p.type_ == "ssn");
+            let found = found.unwrap_or_else(|| panic!("Failed to detect SSN in: {}", text));
+            assert_eq!(found.pattern, ssn);
+            assert_eq!(found.confidence, confidence, "Wrong confidence for: {}", text);
+        }
+
+        // Without a keyword only the dashed form is an SSN
+        for text in ["Order 123 45 6789", "Order 123456789"] {
+            assert!(detect_pii_patterns(text).iter().all(|p| p.type_ != "ssn"), "Should not detect SSN in: {}", text);
+        }
+    }
+
+    #[test]
+    fn test_ssn_issuing_rules() {
+        let invalid = vec![
+            "SSN 000-12-3456",
+            "SSN 666-12-3456",
+            "SSN 912-34-5678",
+            "SSN 123-00-4567",
+            "SSN 123-45-0000",
+            "SSN 078-05-1120",
+        ];
+
+        for text in invalid {
+            let patterns = detect_pii_patterns(text);
+            assert!(patterns.iter().all(|p| p.type_ != "ssn"), "Should not detect SSN in: {}", text);
+        }
     }
 
     #[test]
@@ -254,18 +1065,67 @@ mod tests {
     #[test]
     fn test_credit_card_formats() {
         let formats = vec![
-            "1234-5678-9012-3456",
-            "1234 5678 9012 3456",
-            "1234567890123456",
+            "4532-0151-1283-0366",
+            "4532 0151 1283 0366",
+            "4532015112830366",
+            "3782 822463 10005",       // Amex 4-6-5
+            "3056 930902 5904",        // Diners 4-6-4
+            "4111 1111 1111 1111 110", // 19 digits
+            "4000 0000 00006",         // 13 digits
         ];
         
         for format in formats {
             let patterns = detect_pii_patterns(format);
             assert!(!patterns.is_empty(), "Failed to detect: {}", format);
             assert_eq!(patterns[0].type_, "credit_card");
+            assert_eq!(patterns[0].pattern, format);
         }
     }
 
+    #[test]
+    fn test_card_networks() {
+        let cards = vec![
+            ("4111111111111111", "visa"),
+            ("5555555555554444", "mastercard"),
+            ("2223003122003222", "mastercard"),
+            ("378282246310005", "amex"),
+            ("6011111111111117", "discover"),
+            ("6221260000000000", "discover"),
+            ("30569309025904", "diners_club"),
+            ("3530111333300000", "jcb"),
+            ("6200000000000005", "unionpay"),
+            ("6759649826438453", "maestro"),
+            ("2200000000000004", "mir"),
+        ];
+
+        for (card, network) in cards {
+            let patterns = detect_pii_patterns(card);
+            assert_eq!(patterns.len(), 1, "Failed to detect: {}", card);
+            assert_eq!(patterns[0].card_network.as_deref(), Some(network), "Wrong network for {}", card);
+        }
+
+        // Right length for the digits but not for the network
+        assert!(detect_pii_patterns("3782822463100050").is_empty());
+        // No network issues numbers starting with 1
+        assert!(detect_pii_patterns("1234567890123452").is_empty());
+    }
+
+    #[test]
+    fn test_luhn_failures_suppressed_by_default() {
+        let text = "Card: 4532015112830367";
+        assert!(detect_pii_patterns(text).is_empty());
+
+        let config = PiiConfig { report_luhn_failures: true, ..PiiConfig::default() };
+        let mut window = ChunkWindow::new();
+        window.push(text);
+        let patterns = PiiScanner::with_config(config).scan(&window, window.end());
+        assert_eq!(patterns.len(), 1);
+        // 0.5, raised by the "card" keyword
+        assert_eq!(patterns[0].confidence, 0.6);
+        assert_eq!(patterns[0].supporting_keywords, vec!["card"]);
+        assert_eq!(patterns[0].card_network.as_deref(), Some("visa"));
+    }
+
     #[test]
     fn test_luhn_algorithm() {
         // Valid credit card number (Luhn algorithm)
@@ -280,8 +1140,8 @@ mod tests {
     #[test]
     fn test_invalid_credit_card() {
         let invalid = vec![
-            "1234-5678-9012-345", // Too short
-            "1234-5678-9012-34567", // Too long
+            "4532-0151-1283-036", // Too short
+            "4532-0151-1283-03667", // Too long
             "abcd-efgh-ijkl-mnop", // Non-numeric
         ];
         
@@ -291,6 +1151,137 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_government_ids() {
+        let ids = vec![
+            ("AB 12 34 56 C", "uk_nino", 0.85),
+            ("130 692 544", "ca_sin", 0.85),
+            ("1 85 05 78 006 084 91", "fr_insee", 0.9),
+            ("1 85 05 2A 006 084 35", "fr_insee", 0.9),
+            ("86 095 742 719", "de_steuer_id", 0.85),
+            ("RSSMRA85T10A562S", "it_codice_fiscale", 0.95),
+            ("12345678Z", "es_dni", 0.9),
+            ("X1234567L", "es_nie", 0.9),
+            ("1112.22.333", "nl_bsn", 0.8),
+            ("2341 2341 2346", "in_aadhaar", 0.9),
+            ("ABCPE1234F", "in_pan", 0.8),
+            ("529.982.247-25", "br_cpf", 0.9),
+            ("11.222.333/0001-81", "br_cnpj", 0.9),
+        ];
+
+        for (id, type_, confidence) in ids {
+            let patterns = detect_pii_patterns(&format!("ID: {} end", id));
+            let found = patterns.iter().find(|p| p.type_ == type_);
+            let found = found.unwrap_or_else(|| panic!("Failed to detect {}: {}", type_, id));
+            assert_eq!(found.pattern, id);
+            assert_eq!(found.confidence, confidence, "Wrong confidence for {}", id);
+        }
+    }
+
+    #[test]
+    fn test_government_id_check_digits() {
+        // Distinctive layouts are still reported when the check fails
+        let unvalidated = vec![
+            ("1 85 05 78 006 084 92", "fr_insee", 0.4),
+            ("RSSMRA85T10A562T", "it_codice_fiscale", 0.5),
+            ("12345678A", "es_dni", 0.4),
+            ("529.982.247-26", "br_cpf", 0.5),
+        ];
+        for (id, type_, confidence) in unvalidated {
+            let patterns = detect_pii_patterns(id);
+            let found = patterns.iter().find(|p| p.type_ == type_).unwrap();
+            assert_eq!(found.confidence, confidence, "Wrong confidence for {}", id);
+        }
+
+        // Bare digits and checkless IDs need to validate
+        let invalid = vec![
+            ("130 692 545", "ca_sin"),
+            ("86095742718", "de_steuer_id"),
+            ("1112.22.334", "nl_bsn"),
+            ("2341 2341 2347", "in_aadhaar"),
+            ("52998224726", "br_cpf"),
+            ("111.111.111-11", "br_cpf"),
+            ("GB 12 34 56 C", "uk_nino"),
+            ("ABCDE1234F", "in_pan"),
+        ];
+        for (id, type_) in invalid {
+            let patterns = detect_pii_patterns(id);
+            assert!(patterns.iter().all(|p| p.type_ != type_), "Should not detect {}: {}", type_, id);
+        }
+    }
+
+    #[test]
+    fn test_bare_checksum_ids_need_a_keyword() {
+        let keyworded = vec![
+            ("SIN 130692544", "ca_sin", 0.85),
+            ("Social insurance: 130 692 544", "ca_sin", 0.95),
+            ("BSN 111222333", "nl_bsn", 0.8),
+            ("Steuer-ID 86095742719", "de_steuer_id", 0.85),
+        ];
+        for (text, type_, confidence) in keyworded {
+            let patterns = detect_pii_patterns(text);
+            let found = patterns.iter().find(|p| p.type_ == type_);
+            let found = found.unwrap_or_else(|| panic!("Failed to detect {}: {}", type_, text));
+            assert_eq!(found.confidence, confidence, "Wrong confidence for {}", text);
+        }
+
+        let gated = ["ca_sin", "nl_bsn", "de_steuer_id"];
+        let mut texts = vec!["Invoice 100000009".to_string(), "Ticket 86095742719".to_string()];
+        // Random 9- and 11-digit order numbers, about one in ten passing a checksum
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        for i in 0..2000 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let number = if i % 2 == 0 { state % 900_000_000 + 100_000_000 } else { state % 90_000_000_000 + 10_000_000_000 };
+            texts.push(format!("Order {} shipped", number));
+        }
+        for text in &texts {
+            let patterns = detect_pii_patterns(text);
+            assert!(patterns.iter().all(|p| !gated.contains(&p.type_.as_str())), "Should not detect an ID in {}", text);
+        }
+    }
+
+    #[test]
+    fn test_financial_identifiers() {
+        let cases = vec![
+            ("IBAN DE89 3704 0044 0532 0130 00", "iban", "DE89 3704 0044 0532 0130 00", 0.99),   // Raised by the keyword
+            ("to GB82 WEST 1234 5698 7654 32.", "iban", "GB82 WEST 1234 5698 7654 32", 0.95),
+            ("FR14 2004 1010 0505 0001 3M02 606", "iban", "FR14 2004 1010 0505 0001 3M02 606", 0.95),
+            ("NL91ABNA0417164300", "iban", "NL91ABNA0417164300", 0.95),
+            ("BIC: DEUTDEFF", "swift_bic", "DEUTDEFF", 0.9),
+            ("via CHASUS33XXX", "swift_bic", "CHASUS33XXX", 0.6),
+            ("Routing number 021000021", "aba_routing", "021000021", 0.95),
+            ("011000015", "aba_routing", "011000015", 0.6),
+            ("Account no. 00123456789", "bank_account", "00123456789", 0.7),
+        ];
+
+        for (text, type_, value, confidence) in cases {
+            let patterns = detect_pii_patterns(text);
+            let found = patterns.iter().find(|p| p.type_ == type_);
+            let found = found.unwrap_or_else(|| panic!("Failed to detect {} in: {}", type_, text));
+            assert_eq!(found.pattern, value);
+            assert_eq!(found.confidence, confidence, "Wrong confidence for: {}", text);
+        }
+    }
+
+    #[test]
+    fn test_invalid_financial_identifiers() {
+        let invalid = vec![
+            ("DE89 3704 0044 0532 0130 01", "iban"),    // Fails mod 97
+            ("DE89 3704 0044 0532 0130", "iban"),       // Too short for DE
+            ("QQ89 3704 0044 0532 0130 00", "iban"),    // No such country
+            ("The DOCUMENT was sent", "swift_bic"),     // Ordinary word
+            ("DEUTQQFF", "swift_bic"),                  // No such country
+            ("routing 021000022", "aba_routing"),       // Fails the checksum
+            ("routing 991000021", "aba_routing"),       // Unassigned prefix
+            ("Order 00123456789", "bank_account"),      // No keyword
+        ];
+
+        for (text, type_) in invalid {
+            let patterns = detect_pii_patterns(text);
+            assert!(patterns.iter().all(|p| p.type_ != type_), "Should not detect {} in: {}", type_, text);
+        }
+    }
+
     #[test]
     fn test_ip_address_validation() {
         let valid_ips = vec![
@@ -371,6 +1362,19 @@ mod tests {
         assert_eq!(email_pattern.position, 26); // Email starts at position 26
     }
 
+    #[test]
+    fn test_location_tracking() {
+        let text = "Name: Zoë\nPhone: 123-456-7890";
+        let patterns = detect_pii_patterns(text);
+
+        assert_eq!(patterns.len(), 1);
+        let location = &patterns[0].location;
+        assert_eq!(location.byte_offset, 18);
+        assert_eq!(location.utf16_offset, 17);
+        assert_eq!(location.utf16_length, 12);
+        assert_eq!((location.line, location.column), (2, 8));
+    }
+
     #[test]
     fn test_confidence_scoring() {
         let text = "Phone: 123-456-7890, Card: 4532015112830366";
@@ -406,7 +1410,7 @@ mod tests {
         for i in 0..100 {
             text.push_str(&format!("Phone: 123-456-{:04}, ", i));
             text.push_str(&format!("Email: user{}@example.com, ", i));
-            text.push_str(&format!("SSN: 123-45-{:04}, ", i));
+            text.push_str(&format!("SSN: 123-45-{:04}, ", i + 1));
         }
         
         let start = std::time::Instant::now();
@@ -441,10 +1445,82 @@ mod tests {
         let text = "Version 1.2.3.4 and chapter 1.2.3.4";
         let patterns = detect_pii_patterns(text);
         
-        // Should not detect version numbers as IP addresses
-        // Note: The current regex might detect these as IP addresses
-        // This test documents the current behavior
-        assert_eq!(patterns.len(), 2); // Current behavior: detects as IP addresses
+        // Version and chapter numbers still match, at reduced confidence
+        assert_eq!(patterns.len(), 2);
+        for pattern in &patterns {
+            assert_eq!(pattern.confidence, 0.6);
+            assert_eq!(pattern.negating_keywords, vec!["version", "chapter"]);
+        }
+    }
+
+    #[test]
+    fn test_context_keywords_adjust_confidence() {
+        let cases = vec![
+            ("Phone: 555-123-4567", 0.99, vec!["phone"], vec![]),
+            ("Order #: 555-123-4567", 0.6, vec![], vec!["order #"]),
+            ("555-123-4567", 0.9, vec![], vec![]),
+            ("call 555-123-4567 re: invoice", 0.69, vec!["call"], vec!["invoice"]),
+        ];
+
+        for (text, confidence, supporting, negating) in cases {
+            let patterns = detect_pii_patterns(text);
+            assert_eq!(patterns.len(), 1, "{}", text);
+            assert_eq!(patterns[0].confidence, confidence, "{}", text);
+            assert_eq!(patterns[0].supporting_keywords, supporting, "{}", text);
+            assert_eq!(patterns[0].negating_keywords, negating, "{}", text);
+        }
+
+        // Outside the window a keyword has no effect
+        let text = format!("Phone{}555-123-4567", " ".repeat(40));
+        assert_eq!(detect_pii_patterns(&text)[0].confidence, 0.9);
+    }
+
+    #[test]
+    fn test_configured_keywords_and_window() {
+        let config = PiiConfig {
+            keyword_window: 64,
+            keyword_penalty: 1.0,
+            keywords: HashMap::from([(
+                "phone".to_string(),
+                ContextKeywords { supporting: vec![], negating: vec!["fax-back".to_string()] },
+            )]),
+            ..PiiConfig::default()
+        };
+        let text = format!("Phone{}555-123-4567{}fax-back 555-987-6543", " ".repeat(40), " ".repeat(70));
+        let mut window = ChunkWindow::new();
+        window.push(&text);
+        let patterns = PiiScanner::with_config(config).scan(&window, window.end());
+
+        // The second number is dropped: a full penalty leaves no confidence
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].pattern, "555-123-4567");
+        assert_eq!(patterns[0].supporting_keywords, vec!["phone"]);
+    }
+
+    #[test]
+    fn test_keyword_config_validation() {
+        let cases = vec![
+            (PiiConfig { keyword_window: 1000, ..PiiConfig::default() }, "pii.keyword_window must be at most 256, got 1000"),
+            (PiiConfig { keyword_boost: 1.5, ..PiiConfig::default() }, "pii.keyword_boost must be between 0 and 1, got 1.5"),
+            (
+                PiiConfig { keywords: HashMap::from([("passport".to_string(), ContextKeywords::default())]), ..PiiConfig::default() },
+                "pii.keywords has unknown PII type 'passport'",
+            ),
+            (
+                PiiConfig {
+                    keywords: HashMap::from([(
+                        "ssn".to_string(),
+                        ContextKeywords { supporting: vec![" ".to_string()], negating: vec![] },
+                    )]),
+                    ..PiiConfig::default()
+                },
+                "pii.keywords.ssn.supporting[0] must not be empty",
+            ),
+        ];
+
+        for (config, error) in cases {
+            assert_eq!(config.validate().unwrap_err(), error);
+        }
     }
 
     #[test]
@@ -462,4 +1538,80 @@ mod tests {
             assert_eq!(patterns[0].type_, "phone");
         }
     }
+
+    #[test]
+    fn test_overlapping_matches_are_resolved() {
+        let patterns = detect_pii_patterns("SSN 123456789 on the account");
+
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].type_, "ssn");
+        assert_eq!(patterns[0].alternatives, vec![PIIAlternative {
+            type_: "bank_account".to_string(),
+            pattern: "123456789".to_string(),
+            position: 4,
+            confidence: 0.7,
+        }]);
+
+        // Equal confidence goes to the more specific type, then the longer match
+        let group = vec![
+            PIIPattern { location: TextLocation { byte_length: 9, ..TextLocation::default() }, ..pii_at("bank_account", 0, 0.7) },
+            PIIPattern { location: TextLocation { byte_length: 12, ..TextLocation::default() }, ..pii_at("phone", 0, 0.7) },
+            PIIPattern { location: TextLocation { byte_length: 4, ..TextLocation::default() }, ..pii_at("email", 20, 0.5) },
+        ];
+        let resolved = resolve_overlaps(group);
+        let types: Vec<&str> = resolved.iter().map(|p| p.type_.as_str()).collect();
+        assert_eq!(types, vec!["phone", "email"]);
+        assert_eq!(resolved[0].alternatives[0].type_, "bank_account");
+    }
+
+    fn pii_at(type_: &str, position: usize, confidence: f64) -> PIIPattern {
+        PIIPattern {
+            id: String::new(),
+            type_: type_.to_string(),
+            pattern: String::new(),
+            position,
+            confidence,
+            card_network: None,
+            classification: None,
+            severity: None,
+            supporting_keywords: Vec::new(),
+            negating_keywords: Vec::new(),
+            alternatives: Vec::new(),
+            location: TextLocation::default(),
+            recommended_action: Decision::Allow,
+        }
+    }
+
+    #[test]
+    fn test_scanner_holds_back_open_overlap_groups() {
+        let mut scanner = PiiScanner::new();
+        let mut window = ChunkWindow::new();
+
+        // The SSN ends after the limit, so a bank account match starting
+        // before it could still appear in the next scan
+        window.push("SSN 123456789 on the account");
+        assert!(scanner.scan(&window, 5).is_empty());
+        assert_eq!(scanner.pending_start(), Some(4));
+
+        let patterns = scanner.scan(&window, window.end());
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].alternatives.len(), 1);
+        assert_eq!(scanner.pending_start(), None);
+    }
+
+    #[test]
+    fn test_scanner_holds_back_unsettled_matches() {
+        let mut scanner = PiiScanner::new();
+        let mut window = ChunkWindow::new();
+
+        window.push("Phone: 12345");
+        let holdback = scanner.max_match_bytes();
+        assert!(scanner.scan(&window, window.settled_limit(holdback, false)).is_empty());
+        window.push("67890 ok");
+        let patterns = scanner.scan(&window, window.end());
+
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].pattern, "1234567890");
+        assert_eq!(patterns[0].position, 7);
+    }
 }
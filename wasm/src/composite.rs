@@ -0,0 +1,397 @@
+use std::collections::BTreeSet;
+use serde::{Deserialize, Serialize};
+use crate::policy::{BANNED_PHRASE_DETECTOR, PII_DETECTOR};
+use crate::types::{BannedPhraseMatch, CompositeFinding, Decision, PIIPattern, Severity, TextLocation};
+
+// Composite rules combine banned phrase and PII findings into one finding:
+// "a project codename within 50 words of 'acquisition'", or "'salary' and an
+// employee ID, but not in a template". They are evaluated once the whole
+// document has been scanned, so their terms may sit in different chunks.
+
+/// A rule from `StreamingConfig::composite_rules`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompositeRule {
+    /// Reported as the composite finding's rule; policy type weights and
+    /// always-block rules refer to it as the type
+    pub name: String,
+    pub condition: Condition,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    /// Evidence a composite finding adds to the composite detector
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+fn default_severity() -> Severity {
+    Severity::High
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+/// Condition over the findings of the whole document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Condition {
+    /// At least `min_count` findings match the selector
+    Finding(FindingSelector),
+    /// Findings of the two terms start at most `within` words apart; each
+    /// term's `min_count` counts its findings near the other term
+    Near { terms: [FindingSelector; 2], within: usize },
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    /// Holds when the inner condition does not; contributes no findings
+    Not(Box<Condition>),
+}
+
+/// Findings a condition term refers to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FindingSelector {
+    /// `banned_phrase` or `pii`
+    pub detector: String,
+    /// Banned phrase rule pattern or PII type; any when omitted
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    /// Banned phrase rule category; any when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub min_confidence: f64,
+    #[serde(default = "default_min_count")]
+    pub min_count: usize,
+}
+
+fn default_min_count() -> usize {
+    1
+}
+
+/// Finding fields conditions are evaluated against
+struct Candidate<'a> {
+    detector: &'static str,
+    type_: &'a str,
+    category: Option<&'a str>,
+    confidence: f64,
+    location: TextLocation,
+    id: &'a str,
+}
+
+impl FindingSelector {
+    fn matches(&self, candidate: &Candidate) -> bool {
+        self.detector == candidate.detector
+            && self.type_.as_deref().is_none_or(|t| t == candidate.type_)
+            && self.category.as_deref().is_none_or(|c| Some(c) == candidate.category)
+            && candidate.confidence >= self.min_confidence
+    }
+
+    /// Indices of the matching candidates
+    fn select(&self, candidates: &[Candidate]) -> Vec<usize> {
+        (0..candidates.len()).filter(|&i| self.matches(&candidates[i])).collect()
+    }
+
+    fn validate(&self, path: &str) -> Result<(), String> {
+        if ![BANNED_PHRASE_DETECTOR, PII_DETECTOR].contains(&self.detector.as_str()) {
+            return Err(format!(
+                "{}.detector must be '{}' or '{}', got '{}'",
+                path, BANNED_PHRASE_DETECTOR, PII_DETECTOR, self.detector
+            ));
+        }
+        if self.category.is_some() && self.detector != BANNED_PHRASE_DETECTOR {
+            return Err(format!("{}.category only applies to the '{}' detector", path, BANNED_PHRASE_DETECTOR));
+        }
+        if !(0.0..=1.0).contains(&self.min_confidence) {
+            return Err(format!("{}.min_confidence must be between 0 and 1, got {}", path, self.min_confidence));
+        }
+        if self.min_count == 0 {
+            return Err(format!("{}.min_count must be at least 1", path));
+        }
+        Ok(())
+    }
+}
+
+impl Condition {
+    /// Indices of the findings that satisfy the condition, or `None` when it
+    /// does not hold
+    fn evaluate(&self, candidates: &[Candidate]) -> Option<BTreeSet<usize>> {
+        match self {
+            Condition::Finding(selector) => {
+                let selected = selector.select(candidates);
+                (selected.len() >= selector.min_count).then(|| selected.into_iter().collect())
+            }
+            Condition::Near { terms: [first, second], within } => {
+                let (first_selected, second_selected) = (first.select(candidates), second.select(candidates));
+                let near_first = near(&first_selected, &second_selected, candidates, *within);
+                let near_second = near(&second_selected, &first_selected, candidates, *within);
+                let holds = !near_first.is_empty()
+                    && near_first.len() >= first.min_count
+                    && near_second.len() >= second.min_count;
+                holds.then(|| near_first.into_iter().chain(near_second).collect())
+            }
+            Condition::And(conditions) => {
+                let mut contributing = BTreeSet::new();
+                for condition in conditions {
+                    contributing.extend(condition.evaluate(candidates)?);
+                }
+                Some(contributing)
+            }
+            Condition::Or(conditions) => conditions
+                .iter()
+                .filter_map(|condition| condition.evaluate(candidates))
+                .reduce(|mut contributing, more| {
+                    contributing.extend(more);
+                    contributing
+                }),
+            Condition::Not(condition) => match condition.evaluate(candidates) {
+                Some(_) => None,
+                None => Some(BTreeSet::new()),
+            },
+        }
+    }
+
+    /// Whether the condition can only hold with at least one finding
+    fn requires_finding(&self) -> bool {
+        match self {
+            Condition::Finding(_) | Condition::Near { .. } => true,
+            Condition::And(conditions) => conditions.iter().any(Condition::requires_finding),
+            Condition::Or(conditions) => conditions.iter().all(Condition::requires_finding),
+            Condition::Not(_) => false,
+        }
+    }
+
+    fn validate(&self, path: &str) -> Result<(), String> {
+        match self {
+            Condition::Finding(selector) => selector.validate(&format!("{}.finding", path)),
+            Condition::Near { terms, .. } => {
+                for (i, term) in terms.iter().enumerate() {
+                    term.validate(&format!("{}.near.terms[{}]", path, i))?;
+                }
+                Ok(())
+            }
+            Condition::And(conditions) | Condition::Or(conditions) => {
+                let operator = if matches!(self, Condition::And(_)) { "and" } else { "or" };
+                if conditions.is_empty() {
+                    return Err(format!("{}.{} must list at least one condition", path, operator));
+                }
+                for (i, condition) in conditions.iter().enumerate() {
+                    condition.validate(&format!("{}.{}[{}]", path, operator, i))?;
+                }
+                Ok(())
+            }
+            Condition::Not(condition) => condition.validate(&format!("{}.not", path)),
+        }
+    }
+}
+
+/// Findings of `from` starting within `within` words of a different finding of `to`
+fn near(from: &[usize], to: &[usize], candidates: &[Candidate], within: usize) -> Vec<usize> {
+    let mut to_words: Vec<(usize, usize)> = to.iter().map(|&j| (candidates[j].location.word_index, j)).collect();
+    to_words.sort_unstable();
+    from.iter()
+        .copied()
+        .filter(|&i| {
+            let word = candidates[i].location.word_index;
+            let start = to_words.partition_point(|&(w, _)| w < word.saturating_sub(within));
+            to_words[start..]
+                .iter()
+                .take_while(|&&(w, _)| w <= word.saturating_add(within))
+                .any(|&(_, j)| j != i)
+        })
+        .collect()
+}
+
+impl CompositeRule {
+    fn validate(&self, path: &str) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err(format!("{}.name must not be empty", path));
+        }
+        if !self.weight.is_finite() || self.weight < 0.0 {
+            return Err(format!("{}.weight must be a finite number >= 0, got {}", path, self.weight));
+        }
+        let condition_path = format!("{}.condition", path);
+        self.condition.validate(&condition_path)?;
+        if !self.condition.requires_finding() {
+            return Err(format!("{} must require a finding outside of 'not'", condition_path));
+        }
+        Ok(())
+    }
+}
+
+/// Check every composite rule, naming the offending field
+pub fn validate_composite_rules(rules: &[CompositeRule]) -> Result<(), String> {
+    for (i, rule) in rules.iter().enumerate() {
+        let path = format!("composite_rules[{}]", i);
+        rule.validate(&path)?;
+        if let Some(first) = rules[..i].iter().position(|r| r.name == rule.name) {
+            return Err(format!("{}.name '{}' is already used by composite_rules[{}]", path, rule.name, first));
+        }
+    }
+    Ok(())
+}
+
+/// Composite findings of the rules that hold over a document's findings,
+/// which must already carry their IDs. Each references its contributing
+/// findings and sits at the first of them.
+pub fn evaluate_composite_rules(
+    rules: &[CompositeRule],
+    banned_phrases: &[BannedPhraseMatch],
+    pii_patterns: &[PIIPattern],
+) -> Vec<CompositeFinding> {
+    let candidates: Vec<Candidate> = banned_phrases
+        .iter()
+        .map(|m| Candidate {
+            detector: BANNED_PHRASE_DETECTOR,
+            type_: &m.phrase,
+            category: m.category.as_deref(),
+            confidence: 1.0,
+            location: m.location,
+            id: &m.id,
+        })
+        .chain(pii_patterns.iter().map(|p| Candidate {
+            detector: PII_DETECTOR,
+            type_: &p.type_,
+            category: None,
+            confidence: p.confidence,
+            location: p.location,
+            id: &p.id,
+        }))
+        .collect();
+
+    rules
+        .iter()
+        .filter_map(|rule| {
+            let mut contributing: Vec<usize> = rule.condition.evaluate(&candidates)?.into_iter().collect();
+            contributing.sort_by_key(|&i| (candidates[i].location.byte_offset, i));
+            let first = *contributing.first()?;
+            Some((rule, contributing, first))
+        })
+        .enumerate()
+        .map(|(i, (rule, contributing, first))| CompositeFinding {
+            id: format!("composite-{}", i),
+            rule: rule.name.clone(),
+            severity: rule.severity.as_str().to_string(),
+            weight: rule.weight,
+            finding_ids: contributing.iter().map(|&j| candidates[j].id.to_string()).collect(),
+            location: candidates[first].location,
+            recommended_action: Decision::Allow,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phrase(phrase: &str, word_index: usize) -> BannedPhraseMatch {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("banned_phrase-{}", word_index),
+            "phrase": phrase,
+            "position": word_index * 8,
+            "context": "",
+            "severity": "high",
+            "location": TextLocation { byte_offset: word_index * 8, word_index, ..TextLocation::default() },
+        }))
+        .unwrap()
+    }
+
+    fn pii(type_: &str, word_index: usize) -> PIIPattern {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("pii-{}", word_index),
+            "type_": type_,
+            "pattern": "",
+            "position": word_index * 8,
+            "confidence": 0.9,
+            "location": TextLocation { byte_offset: word_index * 8, word_index, ..TextLocation::default() },
+        }))
+        .unwrap()
+    }
+
+    fn rule(condition: serde_json::Value) -> CompositeRule {
+        serde_json::from_value(serde_json::json!({ "name": "rule", "condition": condition })).unwrap()
+    }
+
+    fn finding_ids(rule: &CompositeRule, banned_phrases: &[BannedPhraseMatch], pii_patterns: &[PIIPattern]) -> Option<Vec<String>> {
+        validate_composite_rules(std::slice::from_ref(rule)).unwrap();
+        evaluate_composite_rules(std::slice::from_ref(rule), banned_phrases, pii_patterns)
+            .pop()
+            .map(|finding| finding.finding_ids)
+    }
+
+    #[test]
+    fn test_near() {
+        let near = rule(serde_json::json!({"near": {"terms": [
+            {"detector": "banned_phrase", "type": "falcon"},
+            {"detector": "banned_phrase", "type": "acquisition"},
+        ], "within": 50}}));
+        let phrases = [phrase("falcon", 3), phrase("acquisition", 60), phrase("falcon", 90), phrase("falcon", 200)];
+
+        assert_eq!(
+            finding_ids(&near, &phrases, &[]).unwrap(),
+            vec!["banned_phrase-60", "banned_phrase-90"]
+        );
+        assert_eq!(finding_ids(&near, &phrases[..1], &[]), None);
+
+        // A finding is never near itself
+        let same = rule(serde_json::json!({"near": {"terms": [
+            {"detector": "banned_phrase", "type": "falcon"},
+            {"detector": "banned_phrase"},
+        ], "within": 5}}));
+        assert_eq!(finding_ids(&same, &phrases[3..], &[]), None);
+
+        let twice = rule(serde_json::json!({"near": {"terms": [
+            {"detector": "banned_phrase", "type": "falcon", "min_count": 2},
+            {"detector": "banned_phrase", "type": "acquisition"},
+        ], "within": 100}}));
+        assert_eq!(finding_ids(&twice, &phrases, &[]).unwrap().len(), 3);
+        assert_eq!(finding_ids(&twice, &phrases[..2], &[]), None);
+    }
+
+    #[test]
+    fn test_boolean_conditions() {
+        let salary = rule(serde_json::json!({"and": [
+            {"finding": {"detector": "banned_phrase", "type": "salary"}},
+            {"or": [
+                {"finding": {"detector": "pii", "type": "employee_id"}},
+                {"finding": {"detector": "pii", "type": "ssn", "min_count": 2}},
+            ]},
+            {"not": {"finding": {"detector": "banned_phrase", "type": "template"}}},
+        ]}));
+
+        let ids = finding_ids(&salary, &[phrase("salary", 1)], &[pii("ssn", 4), pii("employee_id", 2)]).unwrap();
+        assert_eq!(ids, vec!["banned_phrase-1", "pii-2"]);
+        let ids = finding_ids(&salary, &[phrase("salary", 1)], &[pii("ssn", 4), pii("ssn", 7)]).unwrap();
+        assert_eq!(ids, vec!["banned_phrase-1", "pii-4", "pii-7"]);
+        assert_eq!(finding_ids(&salary, &[phrase("salary", 1)], &[pii("ssn", 4)]), None);
+        assert_eq!(finding_ids(&salary, &[phrase("salary", 1), phrase("template", 9)], &[pii("employee_id", 2)]), None);
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let cases = [
+            (serde_json::json!({"finding": {"detector": "secret"}}), "composite_rules[0].condition.finding.detector must be 'banned_phrase' or 'pii', got 'secret'"),
+            (serde_json::json!({"and": []}), "composite_rules[0].condition.and must list at least one condition"),
+            (
+                serde_json::json!({"or": [{"finding": {"detector": "pii", "category": "hr"}}]}),
+                "composite_rules[0].condition.or[0].finding.category only applies to the 'banned_phrase' detector",
+            ),
+            (
+                serde_json::json!({"near": {"terms": [{"detector": "pii"}, {"detector": "pii", "min_count": 0}], "within": 3}}),
+                "composite_rules[0].condition.near.terms[1].min_count must be at least 1",
+            ),
+            (
+                serde_json::json!({"not": {"finding": {"detector": "pii"}}}),
+                "composite_rules[0].condition must require a finding outside of 'not'",
+            ),
+        ];
+        for (condition, error) in cases {
+            assert_eq!(validate_composite_rules(&[rule(condition)]).unwrap_err(), error);
+        }
+
+        let duplicate = rule(serde_json::json!({"finding": {"detector": "pii"}}));
+        assert_eq!(
+            validate_composite_rules(&[duplicate.clone(), duplicate]).unwrap_err(),
+            "composite_rules[1].name 'rule' is already used by composite_rules[0]"
+        );
+    }
+}
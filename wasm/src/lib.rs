@@ -1,16 +1,27 @@
+// Lints the original analysis tests trip; they are kept as written
+#![cfg_attr(test, allow(unused_variables, clippy::manual_range_contains, clippy::useless_vec))]
+
 use wasm_bindgen::prelude::*;
-use serde::{Deserialize, Serialize};
 
-mod analysis;
-mod utils;
-mod types;
-mod streaming;
+pub mod analysis;
+pub mod utils;
+pub mod types;
+pub mod streaming;
+pub mod session;
+pub mod engine;
+pub mod policy;
+pub mod composite;
+pub mod allowlist;
+pub mod redact;
+
+use analysis::{frequency, phrases, pii, secrets, entropy};
+use streaming::StreamingConfig;
+use types::AnalysisRequest;
 
-use analysis::{frequency, phrases, pii, entropy};
-use types::{AnalysisResult, AnalysisRequest};
-use streaming::{StreamingAnalyzer, StreamingConfig, ProcessingStats};
+pub use session::StreamingSession;
 
 #[wasm_bindgen]
+#[derive(Default)]
 pub struct WasmModule {
     // Module state and configuration
 }
@@ -22,18 +33,48 @@ impl WasmModule {
         WasmModule {}
     }
 
-    /// Analyze file content and return security analysis results
-    pub fn analyze_file(&self, content: &str) -> Result<JsValue, JsValue> {
+    /// Analyze file content and return security analysis results, optionally
+    /// with a custom `StreamingConfig` object
+    pub fn analyze_file(&self, content: &str, config: JsValue) -> Result<JsValue, JsValue> {
+        let config = streaming_config(config)?;
         let request = AnalysisRequest {
             content: content.to_string(),
         };
         
-        let result = self.perform_analysis(&request)?;
+        let result = engine::analyze_text(&request.content, config)
+            .map_err(|e| JsValue::from_str(&format!("Analysis error: {}", e)))?;
         
         serde_wasm_bindgen::to_value(&result)
             .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
     }
 
+    /// Analyze raw file bytes, detecting UTF-8, UTF-16 or Windows-1252 encoding,
+    /// optionally with a custom `StreamingConfig` object
+    pub fn analyze_bytes(&self, bytes: &[u8], config: JsValue) -> Result<JsValue, JsValue> {
+        let config = streaming_config(config)?;
+        let result = engine::analyze_bytes(bytes, config)
+            .map_err(|e| JsValue::from_str(&format!("Analysis error: {}", e)))?;
+
+        serde_wasm_bindgen::to_value(&result)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Return a copy of `text` with PII and banned phrases replaced according
+    /// to `options` (a RedactionOptions object, defaults when omitted), finding
+    /// them with `config` (a StreamingConfig object, defaults when omitted)
+    pub fn redact(&self, text: &str, options: JsValue, config: JsValue) -> Result<String, JsValue> {
+        let config = streaming_config(config)?;
+        let options = if options.is_undefined() || options.is_null() {
+            redact::RedactionOptions::default()
+        } else {
+            serde_wasm_bindgen::from_value(options)
+                .map_err(|e| JsValue::from_str(&format!("Options deserialization error: {}", e)))?
+        };
+
+        redact::redact(text, options, config)
+            .map_err(|e| JsValue::from_str(&format!("Redaction error: {}", e)))
+    }
+
     /// Calculate Shannon entropy for text content
     pub fn calculate_entropy(&self, text: &str) -> f64 {
         entropy::calculate_shannon_entropy(text)
@@ -53,139 +94,32 @@ impl WasmModule {
             .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
     }
 
+    /// Detect credentials: cloud keys, API tokens, private keys and assigned passwords
+    pub fn detect_secrets(&self, text: &str) -> Result<JsValue, JsValue> {
+        let secrets = secrets::detect_secrets(text);
+        serde_wasm_bindgen::to_value(&secrets)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
     /// Get top words by frequency
     pub fn get_top_words(&self, text: &str, count: usize) -> Result<JsValue, JsValue> {
         let words = frequency::analyze_word_frequency(text, count);
         serde_wasm_bindgen::to_value(&words)
             .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
     }
-
-    /// Initialize streaming analyzer
-    pub fn init_streaming(&self) -> Result<JsValue, JsValue> {
-        let analyzer = StreamingAnalyzer::init();
-        serde_wasm_bindgen::to_value(&analyzer)
-            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
-    }
-
-    /// Initialize streaming analyzer with custom configuration
-    pub fn init_streaming_with_config(&self, config: JsValue) -> Result<JsValue, JsValue> {
-        let config: StreamingConfig = serde_wasm_bindgen::from_value(config)
-            .map_err(|e| JsValue::from_str(&format!("Config deserialization error: {}", e)))?;
-        
-        let analyzer = StreamingAnalyzer::new(config);
-        serde_wasm_bindgen::to_value(&analyzer)
-            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
-    }
-
-    /// Process chunk with streaming analyzer
-    pub fn process_chunk(&self, analyzer: JsValue, chunk: &str) -> Result<JsValue, JsValue> {
-        let mut analyzer: StreamingAnalyzer = serde_wasm_bindgen::from_value(analyzer)
-            .map_err(|e| JsValue::from_str(&format!("Analyzer deserialization error: {}", e)))?;
-        
-        analyzer.process_chunk(chunk)
-            .map_err(|e| JsValue::from_str(&format!("Processing error: {}", e)))?;
-        
-        serde_wasm_bindgen::to_value(&analyzer)
-            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
-    }
-
-    /// Finalize streaming analysis
-    pub fn finalize_streaming(&self, analyzer: JsValue) -> Result<JsValue, JsValue> {
-        let analyzer: StreamingAnalyzer = serde_wasm_bindgen::from_value(analyzer)
-            .map_err(|e| JsValue::from_str(&format!("Analyzer deserialization error: {}", e)))?;
-        
-        let result = analyzer.finalize()
-            .map_err(|e| JsValue::from_str(&format!("Finalization error: {}", e)))?;
-        
-        serde_wasm_bindgen::to_value(&result)
-            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
-    }
-
-    /// Get processing statistics
-    pub fn get_streaming_stats(&self, analyzer: JsValue) -> Result<JsValue, JsValue> {
-        let analyzer: StreamingAnalyzer = serde_wasm_bindgen::from_value(analyzer)
-            .map_err(|e| JsValue::from_str(&format!("Analyzer deserialization error: {}", e)))?;
-        
-        let stats = analyzer.get_stats();
-        serde_wasm_bindgen::to_value(&stats)
-            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
-    }
 }
 
-impl WasmModule {
-    fn perform_analysis(&self, request: &AnalysisRequest) -> Result<AnalysisResult, JsValue> {
-        let content = &request.content;
-        
-        // Perform all analysis tasks
-        let top_words = frequency::analyze_word_frequency(content, 10);
-        let banned_phrases = phrases::detect_banned_phrases(content);
-        let pii_patterns = pii::detect_pii_patterns(content);
-        let entropy = entropy::calculate_shannon_entropy(content);
-        
-        // Calculate risk score and decision
-        let risk_score = self.calculate_risk_score(&top_words, &banned_phrases, &pii_patterns, entropy);
-        let decision = if risk_score >= 0.6 { "block" } else { "allow" };
-        let reason = self.generate_reason(&banned_phrases, &pii_patterns, entropy);
-        
-        Ok(AnalysisResult {
-            top_words,
-            banned_phrases,
-            pii_patterns,
-            entropy,
-            is_obfuscated: entropy > 4.8,
-            decision: decision.to_string(),
-            reason,
-            risk_score,
-        })
-    }
-
-        fn calculate_risk_score(
-        &self,
-        _top_words: &[(String, usize)],
-        banned_phrases: &[types::BannedPhraseMatch],
-        pii_patterns: &[types::PIIPattern],
-        entropy: f64,
-    ) -> f64 {
-        let banned_weight = 0.4;
-        let pii_weight = 0.3;
-        let entropy_weight = 0.2;
-        let _size_weight = 0.1;
-
-        let banned_score = if banned_phrases.is_empty() { 0.0 } else { 1.0 };
-        let pii_score = if pii_patterns.is_empty() { 0.0 } else { 1.0 };
-        let entropy_score = if entropy > 4.8 { 1.0 } else { entropy / 4.8 };
-
-        banned_score * banned_weight +
-        pii_score * pii_weight +
-        entropy_score * entropy_weight
-    }
-
-    fn generate_reason(
-        &self,
-        banned_phrases: &[types::BannedPhraseMatch],
-        pii_patterns: &[types::PIIPattern],
-        entropy: f64,
-    ) -> String {
-        let mut reasons = Vec::new();
-        
-        if !banned_phrases.is_empty() {
-            reasons.push(format!("Found {} banned phrase(s)", banned_phrases.len()));
-        }
-        
-        if !pii_patterns.is_empty() {
-            reasons.push(format!("Detected {} PII pattern(s)", pii_patterns.len()));
-        }
-        
-        if entropy > 4.8 {
-            reasons.push("High entropy content detected (possible obfuscation)".to_string());
-        }
-        
-        if reasons.is_empty() {
-            "No security concerns detected".to_string()
-        } else {
-            reasons.join("; ")
-        }
-    }
+/// Deserialize and validate an optional `StreamingConfig` object, the way
+/// `StreamingSession::new` does
+fn streaming_config(config: JsValue) -> Result<StreamingConfig, JsValue> {
+    if config.is_undefined() || config.is_null() {
+        return Ok(StreamingConfig::default());
+    }
+    let config: StreamingConfig = serde_wasm_bindgen::from_value(config)
+        .map_err(|e| JsValue::from_str(&format!("Config deserialization error: {}", e)))?;
+    config.validate()
+        .map_err(|e| JsValue::from_str(&format!("Config validation error: {}", e)))?;
+    Ok(config)
 }
 
 #[cfg(test)]
@@ -211,7 +145,7 @@ mod tests {
     fn test_pii_detection() {
         let text = "My phone number is 1234567890 and my SSN is 987654321.";
         let patterns = pii::detect_pii_patterns(text);
-        assert_eq!(patterns.len(), 1); // Only phone number is detected, SSN format is different
+        assert_eq!(patterns.len(), 1); // Only phone number is detected, SSN area numbers 900-999 are never issued
     }
 
     #[test]
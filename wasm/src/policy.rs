@@ -0,0 +1,635 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::types::{BannedPhraseMatch, CompositeFinding, Decision, PIIPattern, SecretFinding};
+
+/// Detector names used in policies and score breakdowns
+pub const BANNED_PHRASE_DETECTOR: &str = "banned_phrase";
+pub const PII_DETECTOR: &str = "pii";
+pub const SECRET_DETECTOR: &str = "secret";
+pub const COMPOSITE_DETECTOR: &str = "composite";
+pub const ENTROPY_DETECTOR: &str = "entropy";
+
+/// Classification of banned phrases found only in disguised form
+pub const EVASION_CLASSIFICATION: &str = "evasion";
+
+/// Declarative risk scoring policy.
+///
+/// Each detector turns its findings into evidence (the sum of per-finding
+/// weights), maps the evidence onto 0..1 with a saturation curve and adds
+/// `weight` times that to the risk score. Always-block rules short-circuit the
+/// score. The default policy reproduces the original fixed weights.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RiskPolicy {
+    pub detectors: DetectorPolicies,
+    pub always_block: Vec<AlwaysBlockRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DetectorPolicies {
+    pub banned_phrase: DetectorPolicy,
+    pub pii: DetectorPolicy,
+    /// Weighted so that a single credential reaches the default block threshold
+    pub secret: DetectorPolicy,
+    /// Findings of composite rules, typed by rule name
+    pub composite: DetectorPolicy,
+    /// Evidence is the measured entropy divided by `entropy_threshold`
+    pub entropy: DetectorPolicy,
+}
+
+impl Default for DetectorPolicies {
+    fn default() -> Self {
+        Self {
+            banned_phrase: DetectorPolicy::new(0.4, Saturation::Step),
+            pii: DetectorPolicy::new(0.3, Saturation::Step),
+            secret: DetectorPolicy::new(0.6, Saturation::Step),
+            composite: DetectorPolicy::new(0.5, Saturation::Step),
+            entropy: DetectorPolicy::new(0.2, Saturation::Linear { saturate_at: 1.0 }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DetectorPolicy {
+    /// Contribution to the risk score once the detector is saturated
+    pub weight: f64,
+    #[serde(default)]
+    pub saturation: Saturation,
+    /// Evidence per finding, keyed by PII or secret type, banned phrase or
+    /// composite rule name (default 1.0)
+    #[serde(default)]
+    pub type_weights: HashMap<String, f64>,
+    /// Factor for findings with a classification, keyed by class such as
+    /// `private` or `loopback` for addresses and `evasion` for disguised
+    /// banned phrases (default 1.0)
+    #[serde(default)]
+    pub classification_weights: HashMap<String, f64>,
+    /// Findings below this confidence are ignored
+    #[serde(default)]
+    pub min_confidence: f64,
+    /// Extra factor for findings at or above a confidence level; the highest
+    /// matching band applies
+    #[serde(default)]
+    pub confidence_multipliers: Vec<ConfidenceBand>,
+}
+
+impl DetectorPolicy {
+    pub fn new(weight: f64, saturation: Saturation) -> Self {
+        Self {
+            weight,
+            saturation,
+            type_weights: HashMap::new(),
+            classification_weights: HashMap::new(),
+            min_confidence: 0.0,
+            confidence_multipliers: Vec::new(),
+        }
+    }
+
+    /// Evidence contributed by a single finding
+    pub fn finding_evidence(&self, type_: &str, classification: Option<&str>, confidence: f64) -> f64 {
+        if confidence < self.min_confidence {
+            return 0.0;
+        }
+        let multiplier = self.confidence_multipliers
+            .iter()
+            .filter(|band| confidence >= band.min_confidence)
+            .max_by(|a, b| a.min_confidence.total_cmp(&b.min_confidence))
+            .map_or(1.0, |band| band.multiplier);
+
+        let classification_weight = classification
+            .and_then(|class| self.classification_weights.get(class))
+            .copied()
+            .unwrap_or(1.0);
+        self.type_weights.get(type_).copied().unwrap_or(1.0) * classification_weight * confidence * multiplier
+    }
+
+    fn validate(&self, path: &str) -> Result<(), String> {
+        check_non_negative(&format!("{}.weight", path), self.weight)?;
+        self.saturation.validate(&format!("{}.saturation", path))?;
+        for (type_, weight) in &self.type_weights {
+            check_non_negative(&format!("{}.type_weights.{}", path, type_), *weight)?;
+        }
+        for (class, weight) in &self.classification_weights {
+            check_non_negative(&format!("{}.classification_weights.{}", path, class), *weight)?;
+        }
+        check_unit(&format!("{}.min_confidence", path), self.min_confidence)?;
+        for (i, band) in self.confidence_multipliers.iter().enumerate() {
+            let band_path = format!("{}.confidence_multipliers[{}]", path, i);
+            check_unit(&format!("{}.min_confidence", band_path), band.min_confidence)?;
+            check_non_negative(&format!("{}.multiplier", band_path), band.multiplier)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfidenceBand {
+    pub min_confidence: f64,
+    pub multiplier: f64,
+}
+
+/// How accumulated evidence maps onto a 0..1 detector score
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "curve", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Saturation {
+    /// Any evidence scores 1
+    #[default]
+    Step,
+    /// Grows linearly, reaching 1 at `saturate_at`
+    Linear { saturate_at: f64 },
+    /// `1 - 0.5^(evidence / half_count)`: each `half_count` halves the remaining gap
+    Exponential { half_count: f64 },
+}
+
+impl Saturation {
+    pub fn apply(&self, evidence: f64) -> f64 {
+        if evidence <= 0.0 {
+            return 0.0;
+        }
+        match self {
+            Saturation::Step => 1.0,
+            Saturation::Linear { saturate_at } => (evidence / saturate_at).min(1.0),
+            Saturation::Exponential { half_count } => 1.0 - 0.5f64.powf(evidence / half_count),
+        }
+    }
+
+    fn validate(&self, path: &str) -> Result<(), String> {
+        match self {
+            Saturation::Step => Ok(()),
+            Saturation::Linear { saturate_at } => check_positive(&format!("{}.saturate_at", path), *saturate_at),
+            Saturation::Exponential { half_count } => check_positive(&format!("{}.half_count", path), *half_count),
+        }
+    }
+}
+
+/// Block outright when enough matching findings are present
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlwaysBlockRule {
+    /// `banned_phrase`, `pii`, `secret` or `composite`
+    pub detector: String,
+    /// PII or secret type, banned phrase or composite rule name to match; any
+    /// finding of the detector when omitted
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    /// Classification to match, such as `evasion`; any when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classification: Option<String>,
+    #[serde(default)]
+    pub min_confidence: f64,
+    #[serde(default = "default_min_count")]
+    pub min_count: usize,
+}
+
+fn default_min_count() -> usize {
+    1
+}
+
+impl AlwaysBlockRule {
+    /// Human-readable summary used in reasons
+    pub fn describe(&self) -> String {
+        let mut description = match &self.type_ {
+            Some(type_) => format!("{} '{}'", self.detector, type_),
+            None => format!("any {}", self.detector),
+        };
+        if let Some(classification) = &self.classification {
+            description.push_str(&format!(" ({})", classification));
+        }
+        if self.min_count > 1 {
+            description.push_str(&format!(" x{}", self.min_count));
+        }
+        if self.min_confidence > 0.0 {
+            description.push_str(&format!(" at confidence >= {}", self.min_confidence));
+        }
+        description
+    }
+
+    /// Whether a single finding counts towards this rule
+    pub fn covers(&self, detector: &str, type_: &str, classification: Option<&str>, confidence: f64) -> bool {
+        self.detector == detector
+            && self.type_.as_deref().is_none_or(|t| t == type_)
+            && self.classification.as_deref().is_none_or(|c| Some(c) == classification)
+            && confidence >= self.min_confidence
+    }
+
+    fn count(&self, findings: &[Finding]) -> usize {
+        findings
+            .iter()
+            .filter(|f| self.covers(f.detector, f.type_, f.classification, f.confidence))
+            .count()
+    }
+}
+
+/// Score of one detector in a `PolicyEvaluation`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectorScore {
+    pub detector: &'static str,
+    pub evidence: f64,
+    /// Evidence after the saturation curve, 0..1
+    pub score: f64,
+    /// Weighted score added to the risk score
+    pub contribution: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyEvaluation {
+    pub risk_score: f64,
+    pub detectors: Vec<DetectorScore>,
+    /// First always-block rule that matched
+    pub always_block: Option<AlwaysBlockMatch>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlwaysBlockMatch {
+    /// Position of the rule in `always_block`
+    pub index: usize,
+    pub rule: AlwaysBlockRule,
+    /// Findings covered by the rule
+    pub count: usize,
+}
+
+struct Finding<'a> {
+    detector: &'static str,
+    type_: &'a str,
+    classification: Option<&'a str>,
+    confidence: f64,
+    /// Rule weight of a banned phrase or composite finding, 1 otherwise
+    weight: f64,
+}
+
+impl RiskPolicy {
+    /// Parse and validate a policy from JSON
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let policy: RiskPolicy = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid policy JSON: {}", e))?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    /// Check every value is in range, naming the offending field
+    pub fn validate(&self) -> Result<(), String> {
+        self.detectors.banned_phrase.validate("policy.detectors.banned_phrase")?;
+        self.detectors.pii.validate("policy.detectors.pii")?;
+        self.detectors.secret.validate("policy.detectors.secret")?;
+        self.detectors.composite.validate("policy.detectors.composite")?;
+        self.detectors.entropy.validate("policy.detectors.entropy")?;
+
+        for (i, rule) in self.always_block.iter().enumerate() {
+            let path = format!("policy.always_block[{}]", i);
+            if ![BANNED_PHRASE_DETECTOR, PII_DETECTOR, SECRET_DETECTOR, COMPOSITE_DETECTOR].contains(&rule.detector.as_str()) {
+                return Err(format!(
+                    "{}.detector must be '{}', '{}', '{}' or '{}', got '{}'",
+                    path, BANNED_PHRASE_DETECTOR, PII_DETECTOR, SECRET_DETECTOR, COMPOSITE_DETECTOR, rule.detector
+                ));
+            }
+            check_unit(&format!("{}.min_confidence", path), rule.min_confidence)?;
+            if rule.min_count == 0 {
+                return Err(format!("{}.min_count must be at least 1", path));
+            }
+        }
+        Ok(())
+    }
+
+    /// Score findings against the policy
+    pub fn evaluate(
+        &self,
+        banned_phrases: &[BannedPhraseMatch],
+        pii_patterns: &[PIIPattern],
+        secrets: &[SecretFinding],
+        composite_findings: &[CompositeFinding],
+        entropy: f64,
+        entropy_threshold: f64,
+    ) -> PolicyEvaluation {
+        let findings: Vec<Finding> = banned_phrases
+            .iter()
+            .map(|m| Finding {
+                detector: BANNED_PHRASE_DETECTOR,
+                type_: &m.phrase,
+                classification: m.evasion.then_some(EVASION_CLASSIFICATION),
+                confidence: 1.0,
+                weight: m.weight,
+            })
+            .chain(pii_patterns.iter().map(|p| Finding {
+                detector: PII_DETECTOR,
+                type_: &p.type_,
+                classification: p.classification.as_deref(),
+                confidence: p.confidence,
+                weight: 1.0,
+            }))
+            .chain(secrets.iter().map(|s| Finding {
+                detector: SECRET_DETECTOR,
+                type_: &s.type_,
+                classification: None,
+                confidence: s.confidence,
+                weight: 1.0,
+            }))
+            .chain(composite_findings.iter().map(|c| Finding {
+                detector: COMPOSITE_DETECTOR,
+                type_: &c.rule,
+                classification: None,
+                confidence: 1.0,
+                weight: c.weight,
+            }))
+            .collect();
+
+        let finding_evidence = |detector: &'static str, policy: &DetectorPolicy| -> f64 {
+            findings
+                .iter()
+                .filter(|f| f.detector == detector)
+                .map(|f| policy.finding_evidence(f.type_, f.classification, f.confidence) * f.weight)
+                .sum()
+        };
+        let detectors: Vec<DetectorScore> = [
+            (BANNED_PHRASE_DETECTOR, &self.detectors.banned_phrase, finding_evidence(BANNED_PHRASE_DETECTOR, &self.detectors.banned_phrase)),
+            (PII_DETECTOR, &self.detectors.pii, finding_evidence(PII_DETECTOR, &self.detectors.pii)),
+            (SECRET_DETECTOR, &self.detectors.secret, finding_evidence(SECRET_DETECTOR, &self.detectors.secret)),
+            (COMPOSITE_DETECTOR, &self.detectors.composite, finding_evidence(COMPOSITE_DETECTOR, &self.detectors.composite)),
+            (ENTROPY_DETECTOR, &self.detectors.entropy, entropy / entropy_threshold),
+        ]
+        .into_iter()
+        .map(|(detector, policy, evidence)| {
+            let score = policy.saturation.apply(evidence);
+            DetectorScore { detector, evidence, score, contribution: score * policy.weight }
+        })
+        .collect();
+
+        let always_block = self.always_block
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| AlwaysBlockMatch { index, rule: rule.clone(), count: rule.count(&findings) })
+            .find(|m| m.count >= m.rule.min_count);
+        let risk_score = if always_block.is_some() {
+            1.0
+        } else {
+            detectors.iter().map(|d| d.contribution).sum::<f64>().min(1.0)
+        };
+
+        PolicyEvaluation { risk_score, detectors, always_block }
+    }
+
+    /// Risk a single finding would carry on its own; `weight` scales its
+    /// evidence, as banned phrase and composite rules do
+    pub fn finding_risk(&self, detector: &str, type_: &str, classification: Option<&str>, confidence: f64, weight: f64) -> f64 {
+        let policy = match detector {
+            BANNED_PHRASE_DETECTOR => &self.detectors.banned_phrase,
+            PII_DETECTOR => &self.detectors.pii,
+            SECRET_DETECTOR => &self.detectors.secret,
+            COMPOSITE_DETECTOR => &self.detectors.composite,
+            _ => return 0.0,
+        };
+        policy.weight * policy.saturation.apply(policy.finding_evidence(type_, classification, confidence) * weight)
+    }
+}
+
+/// Risk scores at which milder actions than blocking kick in.
+///
+/// Blocking uses `StreamingConfig::risk_threshold`. A risk score gets the most
+/// restrictive action whose threshold it reaches; unset levels are skipped.
+/// All are unset by default, leaving only allow and block, which is all the
+/// browser extensions act on.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DecisionThresholds {
+    pub warn: Option<f64>,
+    pub justify: Option<f64>,
+    pub redact: Option<f64>,
+}
+
+impl DecisionThresholds {
+    /// Action for `risk_score`, blocking at `block_threshold`
+    pub fn decide(&self, risk_score: f64, block_threshold: f64) -> Decision {
+        self.crossed(risk_score, block_threshold)
+            .map_or(Decision::Allow, |(decision, _, _)| decision)
+    }
+
+    /// Most restrictive level reached, with the name and value of its threshold
+    pub fn crossed(&self, risk_score: f64, block_threshold: f64) -> Option<(Decision, &'static str, f64)> {
+        if risk_score >= block_threshold {
+            return Some((Decision::Block, "risk_threshold", block_threshold));
+        }
+        [
+            (self.redact, Decision::Redact, "decision_thresholds.redact"),
+            (self.justify, Decision::Justify, "decision_thresholds.justify"),
+            (self.warn, Decision::Warn, "decision_thresholds.warn"),
+        ]
+        .into_iter()
+        .find_map(|(threshold, decision, name)| {
+            threshold.filter(|&t| risk_score >= t).map(|t| (decision, name, t))
+        })
+    }
+
+    /// Check each set level is in range, no lower than the milder levels set
+    /// and below `risk_threshold`, where blocking starts
+    pub fn validate(&self, risk_threshold: f64) -> Result<(), String> {
+        let mut milder: Option<(&str, f64)> = None;
+        for (name, threshold) in [("warn", self.warn), ("justify", self.justify), ("redact", self.redact)] {
+            let Some(value) = threshold else { continue };
+            let path = format!("decision_thresholds.{}", name);
+            check_unit(&path, value)?;
+            if let Some((milder_name, milder_value)) = milder {
+                if value < milder_value {
+                    return Err(format!(
+                        "{} must be >= decision_thresholds.{} ({}), got {}",
+                        path, milder_name, milder_value, value
+                    ));
+                }
+            }
+            if value >= risk_threshold {
+                return Err(format!("{} must be below risk_threshold ({}), got {}", path, risk_threshold, value));
+            }
+            milder = Some((name, value));
+        }
+        Ok(())
+    }
+}
+
+fn check_non_negative(path: &str, value: f64) -> Result<(), String> {
+    if !value.is_finite() || value < 0.0 {
+        return Err(format!("{} must be a finite number >= 0, got {}", path, value));
+    }
+    Ok(())
+}
+
+fn check_positive(path: &str, value: f64) -> Result<(), String> {
+    if !value.is_finite() || value <= 0.0 {
+        return Err(format!("{} must be a finite number > 0, got {}", path, value));
+    }
+    Ok(())
+}
+
+fn check_unit(path: &str, value: f64) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&value) {
+        return Err(format!("{} must be between 0 and 1, got {}", path, value));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pii(type_: &str, confidence: f64) -> PIIPattern {
+        PIIPattern {
+            type_: type_.to_string(),
+            pattern: String::new(),
+            position: 0,
+            confidence,
+            card_network: None,
+            classification: None,
+            severity: None,
+            supporting_keywords: Vec::new(),
+            negating_keywords: Vec::new(),
+            alternatives: Vec::new(),
+            id: String::new(),
+            location: Default::default(),
+            recommended_action: Decision::Allow,
+        }
+    }
+
+    #[test]
+    fn test_default_policy_matches_fixed_weights() {
+        let policy = RiskPolicy::default();
+        let evaluation = policy.evaluate(&[], &[pii("phone", 0.6)], &[], &[], 2.4, 4.8);
+        assert!((evaluation.risk_score - (0.3 + 0.1)).abs() < 1e-9);
+        assert_eq!(evaluation.always_block, None);
+    }
+
+    #[test]
+    fn test_saturation_curves() {
+        assert_eq!(Saturation::Step.apply(0.0), 0.0);
+        assert_eq!(Saturation::Step.apply(0.1), 1.0);
+        assert_eq!(Saturation::Linear { saturate_at: 4.0 }.apply(2.0), 0.5);
+        assert_eq!(Saturation::Linear { saturate_at: 4.0 }.apply(8.0), 1.0);
+        assert_eq!(Saturation::Exponential { half_count: 2.0 }.apply(2.0), 0.5);
+        assert_eq!(Saturation::Exponential { half_count: 2.0 }.apply(4.0), 0.75);
+    }
+
+    #[test]
+    fn test_count_and_confidence_scale_evidence() {
+        let policy = RiskPolicy::from_json(r#"{
+            "detectors": {
+                "pii": {
+                    "weight": 1.0,
+                    "saturation": {"curve": "linear", "saturate_at": 10},
+                    "type_weights": {"ssn": 4, "email": 0},
+                    "min_confidence": 0.5,
+                    "confidence_multipliers": [
+                        {"min_confidence": 0.9, "multiplier": 2},
+                        {"min_confidence": 0.7, "multiplier": 1.5}
+                    ]
+                }
+            }
+        }"#).unwrap();
+        let pii_policy = &policy.detectors.pii;
+
+        assert_eq!(pii_policy.finding_evidence("phone", None, 0.4), 0.0);
+        assert_eq!(pii_policy.finding_evidence("phone", None, 0.6), 0.6);
+        assert_eq!(pii_policy.finding_evidence("phone", None, 0.8), 0.8 * 1.5);
+        assert_eq!(pii_policy.finding_evidence("ssn", None, 0.95), 4.0 * 0.95 * 2.0);
+        assert_eq!(pii_policy.finding_evidence("email", None, 0.85), 0.0);
+
+        let one = policy.evaluate(&[], &[pii("phone", 0.6)], &[], &[], 0.0, 4.8);
+        let many = policy.evaluate(&[], &vec![pii("phone", 0.6); 10], &[], &[], 0.0, 4.8);
+        assert!(one.risk_score < many.risk_score);
+        // Unconfigured detectors keep their defaults
+        assert_eq!(policy.detectors.banned_phrase, DetectorPolicies::default().banned_phrase);
+    }
+
+    #[test]
+    fn test_classification_weights() {
+        let policy = RiskPolicy::from_json(r#"{
+            "detectors": {"pii": {"weight": 1.0, "classification_weights": {"loopback": 0, "private": 0.5}}}
+        }"#).unwrap();
+        let pii_policy = &policy.detectors.pii;
+
+        assert_eq!(pii_policy.finding_evidence("ip_address", Some("public"), 0.9), 0.9);
+        assert_eq!(pii_policy.finding_evidence("ip_address", Some("private"), 0.9), 0.45);
+        assert_eq!(pii_policy.finding_evidence("ip_address", Some("loopback"), 0.9), 0.0);
+        assert_eq!(pii_policy.finding_evidence("email", None, 0.9), 0.9);
+    }
+
+    #[test]
+    fn test_always_block_rule() {
+        let policy = RiskPolicy::from_json(r#"{
+            "detectors": {"pii": {"weight": 0}},
+            "always_block": [{"detector": "pii", "type": "ssn", "min_confidence": 0.9}]
+        }"#).unwrap();
+
+        let low = policy.evaluate(&[], &[pii("ssn", 0.5)], &[], &[], 0.0, 4.8);
+        assert_eq!(low.risk_score, 0.0);
+        assert_eq!(low.always_block, None);
+
+        let high = policy.evaluate(&[], &[pii("ssn", 0.95)], &[], &[], 0.0, 4.8);
+        assert_eq!(high.risk_score, 1.0);
+        let fired = high.always_block.unwrap();
+        assert_eq!((fired.index, fired.count), (0, 1));
+        assert_eq!(fired.rule.describe(), "pii 'ssn' at confidence >= 0.9");
+    }
+
+    #[test]
+    fn test_validation_errors_name_the_field() {
+        let cases = [
+            (r#"{"detectors": {"pii": {"weight": -1}}}"#, "policy.detectors.pii.weight must be a finite number >= 0, got -1"),
+            (r#"{"detectors": {"entropy": {"weight": 1, "saturation": {"curve": "linear", "saturate_at": 0}}}}"#,
+                "policy.detectors.entropy.saturation.saturate_at must be a finite number > 0, got 0"),
+            (r#"{"detectors": {"pii": {"weight": 1, "confidence_multipliers": [{"min_confidence": 2, "multiplier": 1}]}}}"#,
+                "policy.detectors.pii.confidence_multipliers[0].min_confidence must be between 0 and 1, got 2"),
+            (r#"{"always_block": [{"detector": "entropy"}]}"#,
+                "policy.always_block[0].detector must be 'banned_phrase', 'pii', 'secret' or 'composite', got 'entropy'"),
+            (r#"{"always_block": [{"detector": "pii", "min_count": 0}]}"#, "policy.always_block[0].min_count must be at least 1"),
+        ];
+        for (json, expected) in cases {
+            assert_eq!(RiskPolicy::from_json(json).unwrap_err(), expected);
+        }
+
+        let unknown = RiskPolicy::from_json(r#"{"detectors": {"pii": {"wieght": 1}}}"#).unwrap_err();
+        assert!(unknown.starts_with("Invalid policy JSON: unknown field `wieght`"), "{}", unknown);
+        let missing = RiskPolicy::from_json(r#"{"detectors": {"pii": {}}}"#).unwrap_err();
+        assert!(missing.contains("missing field `weight`"), "{}", missing);
+    }
+
+    #[test]
+    fn test_decision_thresholds() {
+        let thresholds = DecisionThresholds {
+            warn: Some(0.2),
+            justify: None,
+            redact: Some(0.5),
+        };
+
+        assert_eq!(thresholds.decide(0.1, 0.8), Decision::Allow);
+        assert_eq!(thresholds.decide(0.3, 0.8), Decision::Warn);
+        assert_eq!(thresholds.decide(0.5, 0.8), Decision::Redact);
+        assert_eq!(thresholds.decide(0.8, 0.8), Decision::Block);
+        // Levels at or above the block threshold are never reached
+        assert_eq!(thresholds.decide(0.6, 0.4), Decision::Block);
+
+        assert_eq!(DecisionThresholds::default().decide(0.59, 0.6), Decision::Allow);
+
+        assert_eq!(thresholds.validate(0.8), Ok(()));
+
+        let invalid = DecisionThresholds { justify: Some(-0.1), ..DecisionThresholds::default() };
+        assert_eq!(invalid.validate(0.6).unwrap_err(), "decision_thresholds.justify must be between 0 and 1, got -0.1");
+    }
+
+    #[test]
+    fn test_decision_thresholds_must_rise_below_risk_threshold() {
+        let cases = [
+            (Some(0.4), Some(0.3), None, "decision_thresholds.justify must be >= decision_thresholds.warn (0.4), got 0.3"),
+            (Some(0.4), None, Some(0.2), "decision_thresholds.redact must be >= decision_thresholds.warn (0.4), got 0.2"),
+            (None, Some(0.6), None, "decision_thresholds.justify must be below risk_threshold (0.6), got 0.6"),
+            (Some(0.2), Some(0.3), Some(0.9), "decision_thresholds.redact must be below risk_threshold (0.6), got 0.9"),
+        ];
+        for (warn, justify, redact, message) in cases {
+            let thresholds = DecisionThresholds { warn, justify, redact };
+            assert_eq!(thresholds.validate(0.6).unwrap_err(), message);
+        }
+
+        // Equal levels are allowed; the more restrictive one wins
+        let equal = DecisionThresholds { warn: Some(0.3), justify: Some(0.3), redact: None };
+        assert_eq!(equal.validate(0.6), Ok(()));
+        assert_eq!(equal.decide(0.3, 0.6), Decision::Justify);
+    }
+}
@@ -0,0 +1,119 @@
+use wasm_bindgen::prelude::*;
+use crate::redact::RedactionOptions;
+use crate::streaming::{StreamingAnalyzer, StreamingConfig};
+
+/// Stateful streaming analysis handle.
+///
+/// The analyzer lives in WASM memory for the whole upload, so JS only holds an
+/// opaque pointer and each chunk crosses the boundary once. Call `free()`
+/// (generated by wasm-bindgen) when the session is no longer needed.
+#[wasm_bindgen]
+pub struct StreamingSession {
+    analyzer: StreamingAnalyzer,
+}
+
+#[wasm_bindgen]
+impl StreamingSession {
+    /// Create a session, optionally with a custom `StreamingConfig` object
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: JsValue) -> Result<StreamingSession, JsValue> {
+        let config = if config.is_undefined() || config.is_null() {
+            StreamingConfig::default()
+        } else {
+            serde_wasm_bindgen::from_value(config)
+                .map_err(|e| JsValue::from_str(&format!("Config deserialization error: {}", e)))?
+        };
+
+        Self::with_config(config)
+            .map_err(|e| JsValue::from_str(&format!("Config validation error: {}", e)))
+    }
+
+    /// Create a session from a JSON `StreamingConfig` string, e.g. a policy
+    /// file maintained by the security team
+    pub fn from_json(json: &str) -> Result<StreamingSession, JsValue> {
+        let config = StreamingConfig::from_json(json)
+            .map_err(|e| JsValue::from_str(&format!("Config validation error: {}", e)))?;
+
+        Self::with_config(config)
+            .map_err(|e| JsValue::from_str(&format!("Config validation error: {}", e)))
+    }
+
+    /// Process the next chunk of file content
+    pub fn push_chunk(&mut self, chunk: &str) -> Result<(), JsValue> {
+        self.analyzer.process_chunk(chunk)
+            .map_err(|e| JsValue::from_str(&format!("Processing error: {}", e)))
+    }
+
+    /// Process the next chunk of raw file bytes; the encoding is detected
+    /// from the start of the file
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        self.analyzer.process_bytes(bytes)
+            .map_err(|e| JsValue::from_str(&format!("Processing error: {}", e)))
+    }
+
+    /// Produce a redacted copy of the pushed content; call before the first chunk.
+    /// `options` is a RedactionOptions object (defaults when omitted)
+    pub fn enable_redaction(&mut self, options: JsValue) -> Result<(), JsValue> {
+        let options = if options.is_undefined() || options.is_null() {
+            RedactionOptions::default()
+        } else {
+            serde_wasm_bindgen::from_value(options)
+                .map_err(|e| JsValue::from_str(&format!("Options deserialization error: {}", e)))?
+        };
+
+        self.analyzer.enable_redaction(options)
+            .map_err(|e| JsValue::from_str(&format!("Redaction error: {}", e)))
+    }
+
+    /// Redacted text settled since the last call; after `finalize()` this
+    /// returns the remaining tail
+    pub fn take_redacted(&mut self) -> String {
+        self.analyzer.take_redacted()
+    }
+
+    /// Get processing statistics for the chunks pushed so far
+    pub fn stats(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.analyzer.get_stats())
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Finalize the analysis and return the results
+    pub fn finalize(&mut self) -> Result<JsValue, JsValue> {
+        let result = self.analyzer.finalize()
+            .map_err(|e| JsValue::from_str(&format!("Finalization error: {}", e)))?;
+
+        serde_wasm_bindgen::to_value(&result)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+}
+
+impl StreamingSession {
+    /// Create a session from an already deserialized configuration
+    pub fn with_config(config: StreamingConfig) -> Result<Self, String> {
+        Ok(Self {
+            analyzer: StreamingAnalyzer::new(config)?,
+        })
+    }
+
+    /// Borrow the underlying analyzer
+    pub fn analyzer(&self) -> &StreamingAnalyzer {
+        &self.analyzer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_keeps_state_between_chunks() {
+        let mut session = StreamingSession::with_config(StreamingConfig::default()).unwrap();
+        session.analyzer.process_chunk("first confidential chunk").unwrap();
+        session.analyzer.process_chunk("second chunk").unwrap();
+
+        assert_eq!(session.analyzer().get_stats().total_chunks, 2);
+
+        let result = session.analyzer.finalize().unwrap();
+        assert_eq!(result.banned_phrases.len(), 1);
+    }
+}
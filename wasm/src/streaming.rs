@@ -1,9 +1,22 @@
-use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
-use crate::types::{AnalysisResult, BannedPhraseMatch, PIIPattern};
+use crate::allowlist::{AllowList, AllowListEntry};
+use crate::analysis::entropy::CharHistogram;
+use crate::analysis::frequency::{TopWordsSketch, WordTokenizer};
+use crate::analysis::custom::{validate_custom_patterns, CustomPiiPattern};
+use crate::analysis::normalize::PhraseNormalization;
+use crate::analysis::phrases::{validate_rules, BannedPhraseRule};
+use crate::analysis::pii::PiiConfig;
+use crate::composite::{validate_composite_rules, CompositeRule};
+use crate::engine::{AnalysisEngine, Findings};
+use crate::policy::{DecisionThresholds, RiskPolicy};
+use crate::redact::{RedactionOptions, Redactor};
+use crate::types::{AnalysisResult, BannedPhraseMatch, PIIAlternative, PIIPattern, SecretFinding, SuppressedFinding};
+use crate::utils::encoding::StreamDecoder;
+use crate::utils::stream::ChunkWindow;
 
 /// Configuration for streaming analysis
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StreamingConfig {
     /// List of stopwords to exclude from frequency analysis
     pub stopwords: Vec<String>,
@@ -11,10 +24,28 @@ pub struct StreamingConfig {
     pub entropy_threshold: f64,
     /// Risk threshold for blocking decisions
     pub risk_threshold: f64,
+    /// Risk thresholds for the warn, justify and redact decisions
+    pub decision_thresholds: DecisionThresholds,
     /// Maximum words to return in frequency analysis
     pub max_words: usize,
-    /// Banned phrases to detect
-    pub banned_phrases: Vec<String>,
+    /// Banned phrase rules; plain strings are case-insensitive whole-word literals
+    pub banned_phrases: Vec<BannedPhraseRule>,
+    /// Normalization that matches disguised banned phrases
+    pub phrase_normalization: PhraseNormalization,
+    /// Number of distinct words tracked by the word frequency summary
+    pub max_tracked_words: usize,
+    /// Hard ceiling on the analyzer's retained state, in bytes
+    pub max_memory_bytes: usize,
+    /// Risk scoring policy
+    pub policy: RiskPolicy,
+    /// Options for the built-in PII detectors
+    pub pii: PiiConfig,
+    /// User-defined PII detectors, run after the built-in ones
+    pub custom_pii_patterns: Vec<CustomPiiPattern>,
+    /// Proximity and boolean rules over the banned phrase and PII findings
+    pub composite_rules: Vec<CompositeRule>,
+    /// Known false positives, suppressed before scoring
+    pub allow_list: Vec<AllowListEntry>,
 }
 
 impl Default for StreamingConfig {
@@ -30,254 +61,269 @@ impl Default for StreamingConfig {
             ].into_iter().map(|s| s.to_string()).collect(),
             entropy_threshold: 4.8,
             risk_threshold: 0.6,
+            decision_thresholds: DecisionThresholds::default(),
             max_words: 10,
-            banned_phrases: vec!["confidential".to_string(), "do not share".to_string()],
+            banned_phrases: vec![BannedPhraseRule::literal("confidential"), BannedPhraseRule::literal("do not share")],
+            phrase_normalization: PhraseNormalization::default(),
+            max_tracked_words: 10_000,
+            // A retained finding takes about 300 bytes, so this keeps the
+            // ~300K findings of a 10 MB document dense with contact details
+            max_memory_bytes: 128 * 1024 * 1024,
+            policy: RiskPolicy::default(),
+            pii: PiiConfig::default(),
+            custom_pii_patterns: Vec::new(),
+            composite_rules: Vec::new(),
+            allow_list: Vec::new(),
         }
     }
 }
 
+impl StreamingConfig {
+    /// Parse and validate a configuration from JSON; omitted fields take their defaults
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let config: StreamingConfig = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid config JSON: {}", e))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check every value is in range, naming the offending field
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.entropy_threshold.is_finite() || self.entropy_threshold <= 0.0 {
+            return Err(format!("entropy_threshold must be a finite number > 0, got {}", self.entropy_threshold));
+        }
+        if !(0.0..=1.0).contains(&self.risk_threshold) {
+            return Err(format!("risk_threshold must be between 0 and 1, got {}", self.risk_threshold));
+        }
+        self.decision_thresholds.validate(self.risk_threshold)?;
+        validate_rules(&self.banned_phrases)?;
+        self.pii.validate()?;
+        validate_custom_patterns(&self.custom_pii_patterns)?;
+        validate_composite_rules(&self.composite_rules)?;
+        AllowList::new(&self.allow_list)?;
+        self.policy.validate()
+    }
+}
+
 /// Streaming analysis state
-#[derive(Debug, Serialize, Deserialize)]
+///
+/// Chunks are never retained: entropy comes from a running character histogram
+/// and word frequencies from a fixed-size summary, so memory stays bounded
+/// regardless of file size. Only a short overlap tail, sized from the longest
+/// phrase, PII pattern and secret, is carried into the next chunk so that findings
+/// straddling a chunk boundary are still detected.
+#[derive(Debug)]
 pub struct StreamingAnalyzer {
-    config: StreamingConfig,
-    word_counts: HashMap<String, usize>,
+    engine: AnalysisEngine,
+    tokenizer: WordTokenizer,
+    word_summary: TopWordsSketch,
+    char_histogram: CharHistogram,
     total_chunks: usize,
-    total_content: String,
-    banned_phrase_matches: Vec<BannedPhraseMatch>,
-    pii_patterns: Vec<PIIPattern>,
+    bytes_processed: usize,
+    window: ChunkWindow,
+    decoder: Option<StreamDecoder>,
+    redactor: Option<Redactor>,
+    findings: Findings,
 }
 
 impl StreamingAnalyzer {
-    /// Initialize a new streaming analyzer with configuration
-    pub fn new(config: StreamingConfig) -> Self {
-        Self {
-            config,
-            word_counts: HashMap::new(),
+    /// Initialize a new streaming analyzer, validating the configuration
+    pub fn new(config: StreamingConfig) -> Result<Self, String> {
+        Ok(Self {
+            word_summary: TopWordsSketch::new(config.max_tracked_words),
+            engine: AnalysisEngine::new(config)?,
+            tokenizer: WordTokenizer::new(),
+            char_histogram: CharHistogram::new(),
             total_chunks: 0,
-            total_content: String::new(),
-            banned_phrase_matches: Vec::new(),
-            pii_patterns: Vec::new(),
-        }
+            bytes_processed: 0,
+            window: ChunkWindow::new(),
+            decoder: None,
+            redactor: None,
+            findings: Findings::default(),
+        })
     }
 
     /// Initialize with default configuration
     pub fn init() -> Self {
-        Self::new(StreamingConfig::default())
+        Self::new(StreamingConfig::default()).expect("default config is valid")
+    }
+
+    pub fn config(&self) -> &StreamingConfig {
+        self.engine.config()
+    }
+
+    /// Produce a redacted copy of the content as it is processed
+    ///
+    /// Must be called before the first chunk. The redacted text is collected
+    /// with `take_redacted`.
+    pub fn enable_redaction(&mut self, options: RedactionOptions) -> Result<(), String> {
+        if self.total_chunks > 0 {
+            return Err("Redaction must be enabled before the first chunk".to_string());
+        }
+        options.validate()?;
+        self.redactor = Some(Redactor::new(options));
+        Ok(())
+    }
+
+    /// Take the redacted text settled since the last call
+    ///
+    /// Text near the end of the latest chunk is held back until the next chunk
+    /// or `finalize` shows whether it starts a finding.
+    pub fn take_redacted(&mut self) -> String {
+        self.redactor.as_mut().map(Redactor::take).unwrap_or_default()
     }
 
     /// Process a chunk of text content
     pub fn process_chunk(&mut self, chunk: &str) -> Result<(), String> {
+        if self.decoder.is_some() {
+            return Err("Cannot mix text and byte chunks in one stream".to_string());
+        }
         self.total_chunks += 1;
-        self.total_content.push_str(chunk);
-        
-        // Process word frequency (excluding stopwords)
-        let words = self.tokenize_text(chunk);
-        for word in words {
-            if !self.config.stopwords.contains(&word) {
-                *self.word_counts.entry(word).or_insert(0) += 1;
-            }
+        self.process_text(chunk)
+    }
+
+    /// Process a chunk of raw file bytes
+    ///
+    /// The encoding is detected from the start of the stream, and a character
+    /// split across chunks is decoded once the rest of it arrives.
+    pub fn process_bytes(&mut self, bytes: &[u8]) -> Result<(), String> {
+        if self.decoder.is_none() && self.total_chunks > 0 {
+            return Err("Cannot mix text and byte chunks in one stream".to_string());
         }
-        
-        // Process banned phrases
-        let chunk_matches = self.detect_banned_phrases_in_chunk(chunk);
-        self.banned_phrase_matches.extend(chunk_matches);
-        
-        // Process PII patterns
-        let chunk_pii = self.detect_pii_patterns_in_chunk(chunk);
-        self.pii_patterns.extend(chunk_pii);
-        
-        Ok(())
+        self.total_chunks += 1;
+        let text = self.decoder.get_or_insert_with(StreamDecoder::new).decode(bytes);
+        self.process_text(&text)
     }
 
     /// Finalize analysis and return results
-    pub fn finalize(&self) -> Result<AnalysisResult, String> {
-        if self.total_content.is_empty() {
+    ///
+    /// Flushes the overlap tail, so findings at the very end of the stream are included.
+    pub fn finalize(&mut self) -> Result<AnalysisResult, String> {
+        if self.total_chunks == 0 {
             return Err("No content processed".to_string());
         }
+
+        if let Some(text) = self.decoder.as_mut().map(StreamDecoder::finish) {
+            self.process_text(&text)?;
+        }
+
+        let engine = &self.engine;
+        let word_summary = &mut self.word_summary;
+        self.tokenizer.finish(&mut |word: &str| {
+            if !engine.is_stopword(word) {
+                word_summary.add(word);
+            }
+        });
+        self.scan_window(true);
         
-        // Get top words (excluding stopwords)
-        let mut sorted_words: Vec<(String, usize)> = self.word_counts
-            .iter()
-            .filter(|(word, _)| !self.config.stopwords.contains(word))
-            .map(|(word, count)| (word.clone(), *count))
-            .collect();
-        sorted_words.sort_by(|a, b| b.1.cmp(&a.1));
-        let top_words: Vec<(String, usize)> = sorted_words.into_iter().take(self.config.max_words).collect();
+        // Get top words (stopwords were excluded while counting)
+        let top_words = self.word_summary.top(self.config().max_words);
         
         // Calculate entropy
-        let entropy = self.calculate_entropy(&self.total_content);
-        
-        // Calculate risk score
-        let risk_score = self.calculate_risk_score(&top_words, &self.banned_phrase_matches, &self.pii_patterns, entropy);
-        
-        // Generate decision and reason
-        let decision = if risk_score >= self.config.risk_threshold { "block" } else { "allow" };
-        let reason = self.generate_reason(&self.banned_phrase_matches, &self.pii_patterns, entropy);
-        
-        Ok(AnalysisResult {
-            top_words,
-            banned_phrases: self.banned_phrase_matches.clone(),
-            pii_patterns: self.pii_patterns.clone(),
-            entropy,
-            is_obfuscated: entropy > self.config.entropy_threshold,
-            decision: decision.to_string(),
-            reason,
-            risk_score,
-        })
+        let entropy = self.char_histogram.entropy();
+        
+        let mut result = self.engine.build_result(top_words, self.findings.clone(), entropy);
+        result.encoding = self.decoder.as_ref().and_then(StreamDecoder::encoding);
+        Ok(result)
     }
 
     /// Get current processing statistics
     pub fn get_stats(&self) -> ProcessingStats {
         ProcessingStats {
             total_chunks: self.total_chunks,
-            total_content_length: self.total_content.len(),
-            unique_words: self.word_counts.len(),
-            banned_phrase_count: self.banned_phrase_matches.len(),
-            pii_pattern_count: self.pii_patterns.len(),
+            total_content_length: self.bytes_processed,
+            unique_words: self.word_summary.len(),
+            banned_phrase_count: self.findings.banned_phrases.len(),
+            pii_pattern_count: self.findings.pii_patterns.len(),
+            secret_count: self.findings.secrets.len(),
+            memory_usage_bytes: self.estimated_memory_bytes(),
         }
     }
 
-    /// Update configuration
-    pub fn update_config(&mut self, config: StreamingConfig) {
-        self.config = config;
+    /// Approximate size of the state retained between chunks
+    pub fn estimated_memory_bytes(&self) -> usize {
+        let phrase_bytes: usize = self.findings.banned_phrases
+            .iter()
+            .map(|m| std::mem::size_of::<BannedPhraseMatch>() + m.phrase.len() + m.context.len() + m.severity.len()
+                + m.category.as_ref().map_or(0, String::len))
+            .sum();
+        let pii_bytes: usize = self.findings.pii_patterns
+            .iter()
+            .map(|p| std::mem::size_of::<PIIPattern>() + p.type_.len() + p.pattern.len() + p.card_network.as_ref().map_or(0, String::len)
+                + p.classification.as_ref().map_or(0, String::len)
+                + p.severity.as_ref().map_or(0, String::len)
+                + p.supporting_keywords.iter().chain(&p.negating_keywords).map(String::len).sum::<usize>()
+                + p.alternatives.iter().map(|a| std::mem::size_of::<PIIAlternative>() + a.type_.len() + a.pattern.len()).sum::<usize>())
+            .sum();
+        let secret_bytes: usize = self.findings.secrets
+            .iter()
+            .map(|s| std::mem::size_of::<SecretFinding>() + s.type_.len() + s.pattern.len())
+            .sum();
+        let suppressed_bytes: usize = self.findings.suppressed
+            .iter()
+            .map(|s| std::mem::size_of::<SuppressedFinding>() + s.detector.len() + s.type_.len() + s.pattern.len()
+                + s.note.as_ref().map_or(0, String::len))
+            .sum();
+
+        self.word_summary.memory_footprint()
+            + self.tokenizer.memory_footprint()
+            + self.char_histogram.memory_footprint()
+            + self.window.text().len()
+            + self.decoder.as_ref().map_or(0, StreamDecoder::pending_len)
+            + self.redactor.as_ref().map_or(0, Redactor::pending_len)
+            + phrase_bytes
+            + pii_bytes
+            + secret_bytes
+            + suppressed_bytes
     }
 
     // Private helper methods
-    fn tokenize_text(&self, text: &str) -> Vec<String> {
-        text.to_lowercase()
-            .split_whitespace()
-            .map(|word| word.chars().filter(|c| c.is_alphanumeric()).collect::<String>())
-            .filter(|word| !word.is_empty())
-            .collect()
-    }
-
-    fn detect_banned_phrases_in_chunk(&self, chunk: &str) -> Vec<BannedPhraseMatch> {
-        let mut matches = Vec::new();
-        let chunk_lower = chunk.to_lowercase();
-        
-        for phrase in &self.config.banned_phrases {
-            let mut start = 0;
-            while let Some(pos) = chunk_lower[start..].find(phrase) {
-                let actual_pos = start + pos;
-                // Get context around the match
-                let context_start = actual_pos.saturating_sub(20);
-                let context_end = (actual_pos + phrase.len() + 20).min(chunk.len());
-                let context = &chunk[context_start..context_end];
-                
-                matches.push(BannedPhraseMatch {
-                    phrase: phrase.clone(),
-                    position: actual_pos,
-                    context: context.to_string(),
-                    severity: "high".to_string(),
-                });
-                start = actual_pos + phrase.len();
-            }
-        }
-        
-        matches
-    }
-
-    fn detect_pii_patterns_in_chunk(&self, chunk: &str) -> Vec<PIIPattern> {
-        use regex::Regex;
-        use lazy_static::lazy_static;
-        
-        lazy_static! {
-            static ref PII_REGEX: Regex = Regex::new(r"\b\d{9,12}\b").unwrap();
-        }
-        
-        let mut patterns = Vec::new();
-        for mat in PII_REGEX.find_iter(chunk) {
-            patterns.push(PIIPattern {
-                type_: "numeric".to_string(),
-                pattern: mat.as_str().to_string(),
-                position: mat.start(),
-                confidence: 0.8,
-            });
-        }
-        
-        patterns
-    }
-
-    fn calculate_entropy(&self, text: &str) -> f64 {
-        use std::collections::HashMap;
-        
-        // Normalize text: lowercase, remove whitespace and punctuation
-        let normalized: String = text
-            .to_lowercase()
-            .chars()
-            .filter(|c| c.is_alphanumeric())
-            .collect();
-
-        if normalized.is_empty() {
-            return 0.0;
-        }
-
-        // Calculate character frequencies
-        let mut char_counts: HashMap<char, usize> = HashMap::new();
-        for ch in normalized.chars() {
-            *char_counts.entry(ch).or_insert(0) += 1;
-        }
-
-        let total_chars = normalized.len() as f64;
-        let mut entropy = 0.0;
+    fn process_text(&mut self, text: &str) -> Result<(), String> {
+        self.bytes_processed += text.len();
+        self.char_histogram.add_text(text);
 
-        // Calculate Shannon entropy: -∑(p_i * log₂(p_i))
-        for count in char_counts.values() {
-            let probability = *count as f64 / total_chars;
-            if probability > 0.0 {
-                entropy -= probability * probability.log2();
+        // Process word frequency (excluding stopwords)
+        let engine = &self.engine;
+        let word_summary = &mut self.word_summary;
+        self.tokenizer.feed(text, &mut |word: &str| {
+            if !engine.is_stopword(word) {
+                word_summary.add(word);
             }
-        }
+        });
 
-        entropy
-    }
-
-    fn calculate_risk_score(
-        &self,
-        _top_words: &[(String, usize)],
-        banned_phrases: &[BannedPhraseMatch],
-        pii_patterns: &[PIIPattern],
-        entropy: f64,
-    ) -> f64 {
-        let banned_weight = 0.4;
-        let pii_weight = 0.3;
-        let entropy_weight = 0.2;
-        let _size_weight = 0.1;
+        // Process banned phrases and PII patterns over the overlap window
+        self.window.push(text);
+        self.scan_window(false);
 
-        let banned_score = if banned_phrases.is_empty() { 0.0 } else { 1.0 };
-        let pii_score = if pii_patterns.is_empty() { 0.0 } else { 1.0 };
-        let entropy_score = if entropy > self.config.entropy_threshold { 1.0 } else { entropy / self.config.entropy_threshold };
-
-        banned_score * banned_weight +
-        pii_score * pii_weight +
-        entropy_score * entropy_weight
+        self.check_memory_limit()
     }
 
-    fn generate_reason(
-        &self,
-        banned_phrases: &[BannedPhraseMatch],
-        pii_patterns: &[PIIPattern],
-        entropy: f64,
-    ) -> String {
-        let mut reasons = Vec::new();
-
-        if !banned_phrases.is_empty() {
-            reasons.push(format!("Found {} banned phrase(s)", banned_phrases.len()));
+    fn check_memory_limit(&self) -> Result<(), String> {
+        let used = self.estimated_memory_bytes();
+        let limit = self.config().max_memory_bytes;
+        if used > limit {
+            return Err(format!(
+                "Memory limit exceeded: analyzer state uses {} bytes, limit is {} bytes",
+                used, limit
+            ));
         }
+        Ok(())
+    }
 
-        if !pii_patterns.is_empty() {
-            reasons.push(format!("Detected {} PII pattern(s)", pii_patterns.len()));
-        }
+    /// Detect findings settled in the window, write out redacted text if
+    /// enabled, then drop everything no longer needed
+    fn scan_window(&mut self, is_final: bool) {
+        let limit = self.engine.settled_limit(&self.window, is_final);
+        let findings = self.engine.scan(&self.window, limit);
 
-        if entropy > self.config.entropy_threshold {
-            reasons.push("High entropy content detected (possible obfuscation)".to_string());
+        let mut keep_from = self.engine.keep_from(limit);
+        if let Some(redactor) = &mut self.redactor {
+            redactor.apply(&self.window, &findings, self.engine.reported_limit(limit));
+            keep_from = keep_from.min(redactor.emitted());
         }
+        self.window.discard_before(keep_from);
 
-        if reasons.is_empty() {
-            "No security concerns detected".to_string()
-        } else {
-            reasons.join("; ")
-        }
+        self.findings.extend(findings);
     }
 }
 
@@ -289,17 +335,22 @@ pub struct ProcessingStats {
     pub unique_words: usize,
     pub banned_phrase_count: usize,
     pub pii_pattern_count: usize,
+    #[serde(default)]
+    pub secret_count: usize,
+    pub memory_usage_bytes: usize,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::engine::analyze_text;
+    use crate::types::{Decision, TextLocation};
 
     #[test]
     fn test_streaming_analyzer_init() {
         let analyzer = StreamingAnalyzer::init();
         assert_eq!(analyzer.total_chunks, 0);
-        assert!(analyzer.total_content.is_empty());
+        assert_eq!(analyzer.bytes_processed, 0);
     }
 
     #[test]
@@ -310,7 +361,7 @@ mod tests {
         let result = analyzer.process_chunk(chunk);
         assert!(result.is_ok());
         assert_eq!(analyzer.total_chunks, 1);
-        assert!(!analyzer.total_content.is_empty());
+        assert_eq!(analyzer.bytes_processed, chunk.len());
     }
 
     #[test]
@@ -322,13 +373,13 @@ mod tests {
         assert!(result.is_ok());
         
         let analysis = result.unwrap();
-        assert_eq!(analysis.decision, "allow");
+        assert_eq!(analysis.decision, Decision::Allow);
         assert!(!analysis.top_words.is_empty());
     }
 
     #[test]
     fn test_finalize_without_content() {
-        let analyzer = StreamingAnalyzer::init();
+        let mut analyzer = StreamingAnalyzer::init();
         let result = analyzer.finalize();
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "No content processed");
@@ -360,15 +411,16 @@ mod tests {
             entropy_threshold: 3.0,
             risk_threshold: 0.3,
             max_words: 5,
-            banned_phrases: vec!["secret".to_string()],
+            banned_phrases: vec![BannedPhraseRule::literal("secret")],
+            ..StreamingConfig::default()
         };
         
-        let mut analyzer = StreamingAnalyzer::new(config);
+        let mut analyzer = StreamingAnalyzer::new(config).unwrap();
         analyzer.process_chunk("This is a secret document with custom content.").unwrap();
         
         let result = analyzer.finalize().unwrap();
         assert!(!result.banned_phrases.is_empty());
-        assert_eq!(result.decision, "block");
+        assert_eq!(result.decision, Decision::Block);
     }
 
     #[test]
@@ -383,6 +435,112 @@ mod tests {
         assert!(stats.unique_words > 0);
     }
 
+    // Chunk Boundary Tests
+    const BOUNDARY_FIXTURES: &[&str] = &[
+        "This document is confidential. Please do not share it with anyone.",
+        "Call 1234567890 or 98765432101 today, and do not share 123456789012.",
+        "DO NOT SHARE: confidential ID 123456789 ü日本 confidential",
+        "Résumé — do not\nshare — do not share, confidentially confidential. 12345678901234",
+        "confidential",
+        "1234567890",
+    ];
+
+    fn analyze_in_chunks(chunks: &[&str]) -> AnalysisResult {
+        let mut analyzer = StreamingAnalyzer::init();
+        for chunk in chunks {
+            analyzer.process_chunk(chunk).unwrap();
+        }
+        analyzer.finalize().unwrap()
+    }
+
+    #[test]
+    fn test_findings_identical_at_every_split_offset() {
+        for fixture in BOUNDARY_FIXTURES {
+            // The one-shot analysis behind `analyze_file`
+            let expected = analyze_text(fixture, StreamingConfig::default()).unwrap();
+
+            for offset in (0..=fixture.len()).filter(|&i| fixture.is_char_boundary(i)) {
+                let (head, tail) = fixture.split_at(offset);
+                let result = analyze_in_chunks(&[head, tail]);
+
+                assert_eq!(result.banned_phrases, expected.banned_phrases, "split at {} of {:?}", offset, fixture);
+                assert_eq!(result.pii_patterns, expected.pii_patterns, "split at {} of {:?}", offset, fixture);
+            }
+        }
+    }
+
+    #[test]
+    fn test_findings_identical_with_single_char_chunks() {
+        for fixture in BOUNDARY_FIXTURES {
+            let expected = analyze_in_chunks(&[fixture]);
+            let pieces: Vec<String> = fixture.chars().map(|c| c.to_string()).collect();
+            let pieces: Vec<&str> = pieces.iter().map(|p| p.as_str()).collect();
+            let result = analyze_in_chunks(&pieces);
+
+            assert_eq!(result.banned_phrases, expected.banned_phrases, "fixture {:?}", fixture);
+            assert_eq!(result.pii_patterns, expected.pii_patterns, "fixture {:?}", fixture);
+        }
+    }
+
+    #[test]
+    fn test_phrase_straddling_chunks_detected_once() {
+        let result = analyze_in_chunks(&["Please do not", " share this."]);
+
+        assert_eq!(result.banned_phrases.len(), 1);
+        assert_eq!(result.banned_phrases[0].phrase, "do not share");
+        assert_eq!(result.banned_phrases[0].position, 7);
+        assert_eq!(result.banned_phrases[0].context, "Please do not share this.");
+    }
+
+    #[test]
+    fn test_pii_split_mid_digits_detected_once() {
+        let result = analyze_in_chunks(&["Phone: 12345", "67890 ok"]);
+
+        assert_eq!(result.pii_patterns.len(), 1);
+        assert_eq!(result.pii_patterns[0].pattern, "1234567890");
+        assert_eq!(result.pii_patterns[0].position, 7);
+    }
+
+    #[test]
+    fn test_longest_grouped_iban_identical_at_every_split_offset() {
+        // A valid 32-character Saint Lucia IBAN, then the same IBAN grown to
+        // 34 characters and 8 spaces, which no country uses
+        let fixture = "LC55 HEMM 0001 0001 0012 0012 0002 3015 and LC55 HEMM 0001 0001 0012 0012 0002 3015 XY end";
+        let expected = analyze_text(fixture, StreamingConfig::default()).unwrap();
+        let ibans: Vec<&str> = expected.pii_patterns.iter().filter(|p| p.type_ == "iban").map(|p| p.pattern.as_str()).collect();
+        assert_eq!(ibans, ["LC55 HEMM 0001 0001 0012 0012 0002 3015"]);
+
+        for offset in 0..=fixture.len() {
+            let (head, tail) = fixture.split_at(offset);
+            let result = analyze_in_chunks(&[head, tail]);
+
+            assert_eq!(result.pii_patterns, expected.pii_patterns, "split at {}", offset);
+        }
+    }
+
+    #[test]
+    fn test_finding_locations_are_absolute() {
+        let result = analyze_in_chunks(&["héllo\nwörld 😀 ", "line two\n  confid", "ential and 1234567890"]);
+
+        let phrase = &result.banned_phrases[0];
+        assert_eq!(phrase.position, 30);
+        assert_eq!(phrase.location, TextLocation {
+            byte_offset: 30,
+            byte_length: 12,
+            utf16_offset: 26,
+            utf16_length: 12,
+            line: 3,
+            column: 3,
+            word_index: 4,
+        });
+
+        let pii = &result.pii_patterns[0];
+        assert_eq!(pii.location.byte_offset, 47);
+        assert_eq!(pii.location.utf16_offset, 43);
+        assert_eq!((pii.location.line, pii.location.column), (3, 20));
+        assert_eq!(pii.location.word_index, 6);
+    }
+
     // Large File Processing Tests
     #[test]
     fn test_large_file_streaming_analysis() {
@@ -402,9 +560,9 @@ mod tests {
         assert!(result.is_ok());
         
         let analysis = result.unwrap();
-        assert!(analysis.banned_phrases.len() > 0);
-        assert!(analysis.pii_patterns.len() > 0);
-        assert!(analysis.top_words.len() > 0);
+        assert!(!analysis.banned_phrases.is_empty());
+        assert!(!analysis.pii_patterns.is_empty());
+        assert!(!analysis.top_words.is_empty());
     }
 
     #[test]
@@ -427,6 +585,49 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_retained_state_does_not_grow_with_file_size() {
+        let mut analyzer = StreamingAnalyzer::new(StreamingConfig {
+            max_tracked_words: 100,
+            ..StreamingConfig::default()
+        }).unwrap();
+
+        let mut sizes = Vec::new();
+        for i in 0..20 {
+            let chunk: String = (0..5000).map(|j| format!("word{:02}x{:04} ", i, j)).collect();
+            analyzer.process_chunk(&chunk).unwrap();
+            sizes.push(analyzer.estimated_memory_bytes());
+        }
+
+        assert!(analyzer.get_stats().unique_words <= 200);
+        assert!(sizes[10..].iter().max() <= sizes[..10].iter().max());
+    }
+
+    #[test]
+    fn test_memory_limit_exceeded() {
+        let mut analyzer = StreamingAnalyzer::new(StreamingConfig {
+            max_memory_bytes: 4096,
+            ..StreamingConfig::default()
+        }).unwrap();
+
+        let chunk = "confidential ".repeat(1000);
+        let err = analyzer.process_chunk(&chunk).unwrap_err();
+        assert!(err.starts_with("Memory limit exceeded"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_streamed_entropy_matches_one_shot() {
+        let text = "Incremental entropy should not depend on how the file is chunked.";
+        let mut analyzer = StreamingAnalyzer::init();
+        for piece in text.split_inclusive(' ') {
+            analyzer.process_chunk(piece).unwrap();
+        }
+
+        let result = analyzer.finalize().unwrap();
+        let expected = crate::analysis::entropy::calculate_shannon_entropy(text);
+        assert!((result.entropy - expected).abs() < 1e-12);
+    }
+
     #[test]
     fn test_error_recovery_during_streaming() {
         let mut analyzer = StreamingAnalyzer::init();
@@ -457,10 +658,11 @@ mod tests {
             entropy_threshold: 4.5,
             risk_threshold: 0.7,
             max_words: 50,
-            banned_phrases: vec!["confidential".to_string(), "secret".to_string()],
+            banned_phrases: vec![BannedPhraseRule::literal("confidential"), BannedPhraseRule::literal("secret")],
+            ..StreamingConfig::default()
         };
         
-        let mut analyzer = StreamingAnalyzer::new(config);
+        let mut analyzer = StreamingAnalyzer::new(config).unwrap();
         
         let chunk_size = 1024 * 1024;
         let num_chunks = 5; // 5MB
@@ -555,9 +757,9 @@ mod tests {
         let mut chunk = String::new();
         let base_words = vec!["word", "text", "content", "data"];
         
-        let special_words = if chunk_index % 10 == 0 {
+        let special_words = if chunk_index.is_multiple_of(10) {
             vec!["confidential", "secret", "private"]
-        } else if chunk_index % 5 == 0 {
+        } else if chunk_index.is_multiple_of(5) {
             vec!["phone", "1234567890", "email", "test@example.com"]
         } else {
             vec![]
@@ -642,6 +844,31 @@ mod tests {
         chunk.truncate(size);
         chunk
     }
-}
 
+    #[test]
+    fn test_config_from_json() {
+        let config = StreamingConfig::from_json(r#"{
+            "risk_threshold": 0.5,
+            "policy": {"detectors": {"pii": {"weight": 0.6, "type_weights": {"ssn": 3}}}}
+        }"#).unwrap();
+
+        assert_eq!(config.risk_threshold, 0.5);
+        assert_eq!(config.max_words, 10);
+        assert_eq!(config.policy.detectors.pii.weight, 0.6);
+        assert_eq!(config.policy.detectors.pii.type_weights["ssn"], 3.0);
+    }
+
+    #[test]
+    fn test_invalid_config_rejected() {
+        let err = StreamingConfig::from_json(r#"{"risk_threshold": 1.5}"#).unwrap_err();
+        assert_eq!(err, "risk_threshold must be between 0 and 1, got 1.5");
+
+        let err = StreamingConfig::from_json(r#"{"policy": {"detectors": {"banned_phrase": {"weight": "high"}}}}"#).unwrap_err();
+        assert!(err.starts_with("Invalid config JSON: invalid type"), "{}", err);
 
+        let mut config = StreamingConfig::default();
+        config.policy.detectors.entropy.weight = f64::NAN;
+        let err = StreamingAnalyzer::new(config).unwrap_err();
+        assert_eq!(err, "policy.detectors.entropy.weight must be a finite number >= 0, got NaN");
+    }
+}
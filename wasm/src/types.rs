@@ -1,34 +1,285 @@
 use serde::{Deserialize, Serialize};
+use crate::utils::encoding::TextEncoding;
+use crate::utils::text::TextLocator;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisRequest {
     pub content: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AnalysisResult {
     pub top_words: Vec<(String, usize)>,
     pub banned_phrases: Vec<BannedPhraseMatch>,
     pub pii_patterns: Vec<PIIPattern>,
+    #[serde(default)]
+    pub secrets: Vec<SecretFinding>,
+    /// Composite rules that hold over the other findings
+    #[serde(default)]
+    pub composite_findings: Vec<CompositeFinding>,
+    /// Findings suppressed by the allow-list, left out of scoring, in
+    /// document order
+    #[serde(default)]
+    pub suppressed_findings: Vec<SuppressedFinding>,
     pub entropy: f64,
     pub is_obfuscated: bool,
-    pub decision: String,
+    pub decision: Decision,
+    /// Human-readable summary of `reasons`
     pub reason: String,
+    #[serde(default)]
+    pub reasons: Vec<Reason>,
     pub risk_score: f64,
+    /// Encoding detected for byte input; absent when text was passed in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<TextEncoding>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BannedPhraseMatch {
+    /// Identifier referenced by `Reason::finding_ids`
+    #[serde(default)]
+    pub id: String,
     pub phrase: String,
     pub position: usize,
     pub context: String,
     pub severity: String,
+    #[serde(default)]
+    pub location: TextLocation,
+    #[serde(default)]
+    pub recommended_action: Decision,
+    /// Matched only after normalizing disguised text, e.g. "c0nf1dential"
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub evasion: bool,
+    /// Category label of the rule that matched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Evidence the match adds to the banned phrase detector
+    #[serde(default = "default_weight")]
+    pub weight: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn default_weight() -> f64 {
+    1.0
+}
+
+/// Severity configured for a banned phrase rule or custom PII detector
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PIIPattern {
+    /// Identifier referenced by `Reason::finding_ids`
+    #[serde(default)]
+    pub id: String,
     pub type_: String,
     pub pattern: String,
     pub position: usize,
     pub confidence: f64,
+    /// Card network identified from the IIN, for credit cards
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card_network: Option<String>,
+    /// Address range of IP addresses and internal hostnames: `public`,
+    /// `private`, `loopback`, `link_local`, `documentation` or `reserved`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classification: Option<String>,
+    /// `low`, `medium`, `high` or `critical`, for custom detectors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    /// Context keywords near the match that raised its confidence
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub supporting_keywords: Vec<String>,
+    /// Context keywords near the match that lowered its confidence
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub negating_keywords: Vec<String>,
+    /// Overlapping matches of other detectors that this finding outranked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<PIIAlternative>,
+    #[serde(default)]
+    pub location: TextLocation,
+    #[serde(default)]
+    pub recommended_action: Decision,
+}
+
+/// A composite rule that holds, with the findings that satisfy it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompositeFinding {
+    /// Identifier referenced by `Reason::finding_ids`
+    pub id: String,
+    /// Name of the rule
+    pub rule: String,
+    pub severity: String,
+    /// Evidence the finding adds to the composite detector
+    pub weight: f64,
+    /// IDs of the banned phrase and PII findings that satisfy the rule, in
+    /// document order
+    pub finding_ids: Vec<String>,
+    /// Location of the first contributing finding
+    pub location: TextLocation,
+    #[serde(default)]
+    pub recommended_action: Decision,
+}
+
+/// A finding suppressed by an allow-list entry, kept for audit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuppressedFinding {
+    /// `banned_phrase`, `pii` or `secret`
+    pub detector: String,
+    /// PII or secret type, or banned phrase
+    pub type_: String,
+    /// Matched text; masked for secrets
+    pub pattern: String,
+    pub position: usize,
+    pub confidence: f64,
+    pub location: TextLocation,
+    /// Index of the entry in `allow_list`
+    pub allow_list_index: usize,
+    /// Note of the entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// A PII match dropped in favour of an overlapping finding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PIIAlternative {
+    pub type_: String,
+    pub pattern: String,
+    pub position: usize,
+    pub confidence: f64,
+}
+
+/// A credential: cloud key, API token, private key or assigned password
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecretFinding {
+    /// Identifier referenced by `Reason::finding_ids`
+    #[serde(default)]
+    pub id: String,
+    pub type_: String,
+    /// Start of the value followed by a mask; never the whole secret
+    pub pattern: String,
+    pub position: usize,
+    pub confidence: f64,
+    /// Shannon entropy of the value, for generic assignments scored by it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entropy: Option<f64>,
+    #[serde(default)]
+    pub location: TextLocation,
+    #[serde(default)]
+    pub recommended_action: Decision,
+}
+
+/// Action for a file or a single finding, ordered from least to most restrictive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    #[default]
+    Allow,
+    /// Let the upload through after showing a warning
+    Warn,
+    /// Require the user to give a justification before uploading
+    Justify,
+    /// Upload only with the findings redacted
+    Redact,
+    Block,
+}
+
+impl Decision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Decision::Allow => "allow",
+            Decision::Warn => "warn",
+            Decision::Justify => "justify",
+            Decision::Redact => "redact",
+            Decision::Block => "block",
+        }
+    }
+}
+
+/// One entry of the breakdown explaining a decision
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reason {
+    pub code: ReasonCode,
+    /// Detector responsible, or `policy` for the decision threshold entry
+    pub detector: String,
+    /// Amount added to the risk score
+    pub contribution: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<ThresholdCrossed>,
+    /// IDs of the findings behind this entry
+    pub finding_ids: Vec<String>,
+}
+
+/// Stable reason identifier, suitable as a localization key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasonCode {
+    AlwaysBlockRule,
+    BannedPhrases,
+    PiiPatterns,
+    Secrets,
+    CompositeRules,
+    Entropy,
+    DecisionThreshold,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdCrossed {
+    /// Config field holding the threshold, e.g. `risk_threshold`
+    pub name: String,
+    pub value: f64,
+    pub observed: f64,
+}
+
+/// Where a finding sits in the analyzed document.
+///
+/// Offsets are absolute (not relative to a streaming chunk). UTF-16 offsets
+/// and lengths index JS strings directly; lines and columns are 1-based, with
+/// columns counted in chars. `word_index` is the 0-based index of the word
+/// the finding starts in, or of the next word when it starts between words.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TextLocation {
+    pub byte_offset: usize,
+    pub byte_length: usize,
+    pub utf16_offset: usize,
+    pub utf16_length: usize,
+    pub line: usize,
+    pub column: usize,
+    #[serde(default)]
+    pub word_index: usize,
+}
+
+impl TextLocation {
+    /// Location of the span `[start, end)` of the text covered by `locator`
+    pub fn from_span(locator: &mut TextLocator, start: usize, end: usize) -> Self {
+        let start = locator.position(start);
+        let end = locator.position(end);
+
+        Self {
+            byte_offset: start.byte_offset,
+            byte_length: end.byte_offset - start.byte_offset,
+            utf16_offset: start.utf16_offset,
+            utf16_length: end.utf16_offset - start.utf16_offset,
+            line: start.line,
+            column: start.column,
+            // Starting mid-word means the word was already counted
+            word_index: start.words - usize::from(start.in_word),
+        }
+    }
 }
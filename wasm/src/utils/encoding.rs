@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+
+/// Bytes buffered before the encoding is chosen.
+///
+/// Detection always looks at the same prefix, so the choice does not depend on
+/// how the input was chunked. Bytes past the prefix that are invalid in the
+/// chosen encoding decode to U+FFFD.
+pub const SNIFF_BYTES: usize = 64 * 1024;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Text encodings recognized by `StreamDecoder`, serialized with their WHATWG labels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    /// Also used for Latin-1, which browsers treat as an alias
+    #[serde(rename = "windows-1252")]
+    Windows1252,
+}
+
+impl TextEncoding {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "utf-8",
+            TextEncoding::Utf16Le => "utf-16le",
+            TextEncoding::Utf16Be => "utf-16be",
+            TextEncoding::Windows1252 => "windows-1252",
+        }
+    }
+}
+
+/// Detect the encoding of a byte prefix, returning it with the BOM length to skip.
+///
+/// A BOM wins. Otherwise NUL bytes concentrated on odd or even offsets mark
+/// UTF-16 LE or BE text, valid UTF-8 is UTF-8, and anything else is
+/// Windows-1252. When the sample is `truncated`, a UTF-8 sequence cut off at
+/// its end still counts as valid.
+pub fn detect_encoding(sample: &[u8], truncated: bool) -> (TextEncoding, usize) {
+    if sample.starts_with(UTF8_BOM) {
+        return (TextEncoding::Utf8, UTF8_BOM.len());
+    }
+    if sample.starts_with(UTF16_LE_BOM) {
+        return (TextEncoding::Utf16Le, UTF16_LE_BOM.len());
+    }
+    if sample.starts_with(UTF16_BE_BOM) {
+        return (TextEncoding::Utf16Be, UTF16_BE_BOM.len());
+    }
+
+    let pairs = sample.len() / 2;
+    let even_zeros = sample.iter().step_by(2).take(pairs).filter(|&&b| b == 0).count();
+    let odd_zeros = sample.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+    if pairs > 0 {
+        if odd_zeros * 4 >= pairs && even_zeros * 8 <= odd_zeros {
+            return (TextEncoding::Utf16Le, 0);
+        }
+        if even_zeros * 4 >= pairs && odd_zeros * 8 <= even_zeros {
+            return (TextEncoding::Utf16Be, 0);
+        }
+    }
+
+    match std::str::from_utf8(sample) {
+        Ok(_) => (TextEncoding::Utf8, 0),
+        Err(e) if truncated && e.error_len().is_none() => (TextEncoding::Utf8, 0),
+        Err(_) => (TextEncoding::Windows1252, 0),
+    }
+}
+
+/// Incremental decoder from raw file bytes to text.
+///
+/// The first `SNIFF_BYTES` are buffered for encoding detection. After that
+/// every chunk is decoded immediately, except for a multi-byte sequence cut by
+/// the chunk boundary, which is held until the next chunk completes it.
+#[derive(Debug, Clone, Default)]
+pub struct StreamDecoder {
+    encoding: Option<TextEncoding>,
+    pending: Vec<u8>,
+}
+
+impl StreamDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encoding chosen for the stream, once enough bytes have been seen
+    pub fn encoding(&self) -> Option<TextEncoding> {
+        self.encoding
+    }
+
+    /// Bytes held back for detection or an incomplete sequence
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Decode the next chunk, returning the text that is complete so far
+    pub fn decode(&mut self, bytes: &[u8]) -> String {
+        self.pending.extend_from_slice(bytes);
+        if self.encoding.is_none() && self.pending.len() < SNIFF_BYTES {
+            return String::new();
+        }
+        self.decode_pending(false)
+    }
+
+    /// Decode everything still held; incomplete sequences become U+FFFD
+    pub fn finish(&mut self) -> String {
+        self.decode_pending(true)
+    }
+
+    fn decode_pending(&mut self, is_final: bool) -> String {
+        let encoding = match self.encoding {
+            Some(encoding) => encoding,
+            None => {
+                let truncated = self.pending.len() > SNIFF_BYTES || !is_final;
+                let sample_len = self.pending.len().min(SNIFF_BYTES);
+                let (encoding, bom_len) = detect_encoding(&self.pending[..sample_len], truncated);
+                self.pending.drain(..bom_len);
+                self.encoding = Some(encoding);
+                encoding
+            }
+        };
+
+        let (text, consumed) = match encoding {
+            TextEncoding::Utf8 => decode_utf8(&self.pending, is_final),
+            TextEncoding::Utf16Le => decode_utf16(&self.pending, is_final, u16::from_le_bytes),
+            TextEncoding::Utf16Be => decode_utf16(&self.pending, is_final, u16::from_be_bytes),
+            TextEncoding::Windows1252 => (decode_windows_1252(&self.pending), self.pending.len()),
+        };
+        self.pending.drain(..consumed);
+        text
+    }
+}
+
+/// Decode UTF-8, stopping before an incomplete trailing sequence unless `is_final`
+fn decode_utf8(bytes: &[u8], is_final: bool) -> (String, usize) {
+    let mut text = String::with_capacity(bytes.len());
+    let mut rest = bytes;
+
+    loop {
+        match std::str::from_utf8(rest) {
+            Ok(valid) => {
+                text.push_str(valid);
+                return (text, bytes.len());
+            }
+            Err(e) => {
+                let (valid, after) = rest.split_at(e.valid_up_to());
+                // `valid_up_to` marks a prefix that is valid UTF-8
+                text.push_str(std::str::from_utf8(valid).unwrap());
+                match e.error_len() {
+                    Some(len) => {
+                        text.push(char::REPLACEMENT_CHARACTER);
+                        rest = &after[len..];
+                    }
+                    None if is_final => {
+                        text.push(char::REPLACEMENT_CHARACTER);
+                        return (text, bytes.len());
+                    }
+                    None => return (text, bytes.len() - after.len()),
+                }
+            }
+        }
+    }
+}
+
+/// Decode UTF-16 code units, holding back an odd byte or a lone high
+/// surrogate at the end unless `is_final`
+fn decode_utf16(bytes: &[u8], is_final: bool, unit: fn([u8; 2]) -> u16) -> (String, usize) {
+    let mut units: Vec<u16> = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]])).collect();
+    let mut consumed = units.len() * 2;
+
+    if !is_final && units.last().is_some_and(|&u| (0xD800..0xDC00).contains(&u)) {
+        units.pop();
+        consumed -= 2;
+    }
+
+    let mut text: String = char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect();
+    if is_final && consumed < bytes.len() {
+        text.push(char::REPLACEMENT_CHARACTER);
+        consumed = bytes.len();
+    }
+    (text, consumed)
+}
+
+/// Characters for bytes 0x80-0x9F; the five unassigned bytes map to the
+/// matching C1 controls, as in the WHATWG encoding standard
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+fn decode_windows_1252(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80..=0x9F => WINDOWS_1252_HIGH[(b - 0x80) as usize],
+            _ => b as char,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_in_chunks(bytes: &[u8], chunk_size: usize) -> (String, Option<TextEncoding>) {
+        let mut decoder = StreamDecoder::new();
+        let mut text = String::new();
+        for chunk in bytes.chunks(chunk_size) {
+            text.push_str(&decoder.decode(chunk));
+        }
+        text.push_str(&decoder.finish());
+        (text, decoder.encoding())
+    }
+
+    fn utf16(text: &str, little_endian: bool) -> Vec<u8> {
+        text.encode_utf16()
+            .flat_map(|u| if little_endian { u.to_le_bytes() } else { u.to_be_bytes() })
+            .collect()
+    }
+
+    #[test]
+    fn test_detects_boms() {
+        assert_eq!(detect_encoding(b"\xEF\xBB\xBFabc", false), (TextEncoding::Utf8, 3));
+        assert_eq!(detect_encoding(b"\xFF\xFEa\x00", false), (TextEncoding::Utf16Le, 2));
+        assert_eq!(detect_encoding(b"\xFE\xFF\x00a", false), (TextEncoding::Utf16Be, 2));
+    }
+
+    #[test]
+    fn test_detects_without_bom() {
+        assert_eq!(detect_encoding(&utf16("plain text", true), false).0, TextEncoding::Utf16Le);
+        assert_eq!(detect_encoding(&utf16("plain text", false), false).0, TextEncoding::Utf16Be);
+        assert_eq!(detect_encoding("café".as_bytes(), false).0, TextEncoding::Utf8);
+        assert_eq!(detect_encoding(b"caf\xE9", false).0, TextEncoding::Windows1252);
+        // A sequence cut off by the end of the sample is still UTF-8
+        assert_eq!(detect_encoding(&"café".as_bytes()[..4], true).0, TextEncoding::Utf8);
+    }
+
+    #[test]
+    fn test_utf8_sequence_split_across_chunks() {
+        let text = "héllo wörld 😀 naïve";
+        for chunk_size in 1..=text.len() {
+            let (decoded, encoding) = decode_in_chunks(text.as_bytes(), chunk_size);
+            assert_eq!(decoded, text);
+            assert_eq!(encoding, Some(TextEncoding::Utf8));
+        }
+    }
+
+    #[test]
+    fn test_utf16_split_across_chunks() {
+        let text = "confidential 😀 données";
+        for little_endian in [true, false] {
+            let mut bytes = if little_endian { UTF16_LE_BOM.to_vec() } else { UTF16_BE_BOM.to_vec() };
+            bytes.extend(utf16(text, little_endian));
+            for chunk_size in 1..=bytes.len() {
+                assert_eq!(decode_in_chunks(&bytes, chunk_size).0, text);
+            }
+        }
+    }
+
+    #[test]
+    fn test_windows_1252() {
+        let (decoded, encoding) = decode_in_chunks(b"\x93caf\xE9\x94 \x80 5", 3);
+        assert_eq!(decoded, "\u{201C}café\u{201D} € 5");
+        assert_eq!(encoding, Some(TextEncoding::Windows1252));
+    }
+
+    #[test]
+    fn test_detection_ignores_chunking() {
+        // Invalid UTF-8 after a long ASCII prefix is only seen if the sniffed
+        // prefix reaches it, whatever the chunk size
+        let mut bytes = vec![b'a'; SNIFF_BYTES - 2];
+        bytes.extend(b"\xE9b");
+        for chunk_size in [1000, SNIFF_BYTES / 2, bytes.len()] {
+            assert_eq!(decode_in_chunks(&bytes, chunk_size).1, Some(TextEncoding::Windows1252));
+        }
+    }
+
+    #[test]
+    fn test_truncated_input_is_replaced() {
+        assert_eq!(decode_in_chunks(b"\xEF\xBB\xBFab\xE2\x82", 2).0, "ab\u{FFFD}");
+        assert_eq!(decode_in_chunks(b"\xFF\xFEa\x00b", 2).0, "a\u{FFFD}");
+    }
+}
@@ -1,5 +1,9 @@
 pub mod text;
 pub mod stream;
+pub mod encoding;
+pub mod hash;
 
 pub use text::*;
 pub use stream::*;
+pub use encoding::*;
+pub use hash::*;
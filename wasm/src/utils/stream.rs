@@ -1,3 +1,6 @@
+use regex::Regex;
+use crate::utils::text::{ceil_char_boundary, floor_char_boundary, TextLocator, TextPosition};
+
 pub struct FileStream {
     pub chunk_size: usize,
     pub buffer: Vec<u8>,
@@ -25,3 +28,185 @@ impl FileStream {
         }
     }
 }
+
+/// Widest UTF-8 encoding of a single char, used to size look-around margins
+pub const MAX_CHAR_BYTES: usize = 4;
+
+/// Sliding window over a chunked text stream.
+///
+/// The window holds the unsettled tail of the previous chunks followed by the
+/// newest chunk, and all offsets it hands out are absolute document offsets.
+/// Detectors keep their own resume cursor, so a match found in the overlap is
+/// reported exactly once and matches straddling a chunk boundary are seen
+/// whole.
+#[derive(Debug, Default)]
+pub struct ChunkWindow {
+    text: String,
+    base: usize,
+    base_position: TextPosition,
+}
+
+impl ChunkWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the next chunk
+    pub fn push(&mut self, chunk: &str) {
+        self.text.push_str(chunk);
+    }
+
+    /// Text currently held by the window
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Absolute offset of the first byte held by the window
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    /// Locator over the held text, positioned at its place in the document
+    pub fn locator(&self) -> TextLocator<'_> {
+        TextLocator::new(&self.text, self.base_position)
+    }
+
+    /// Relative offset of an absolute offset, for use with `locator`
+    pub fn relative(&self, offset: usize) -> usize {
+        offset.saturating_sub(self.base)
+    }
+
+    /// Absolute offset just past the last byte held by the window
+    pub fn end(&self) -> usize {
+        self.base + self.text.len()
+    }
+
+    /// Absolute offset before which matches can be settled.
+    ///
+    /// A match starting before the limit has at least `holdback` bytes of
+    /// lookahead in the window. Once the stream is complete everything is settled.
+    pub fn settled_limit(&self, holdback: usize, is_final: bool) -> usize {
+        if is_final {
+            self.end()
+        } else {
+            self.base + floor_char_boundary(&self.text, self.text.len().saturating_sub(holdback))
+        }
+    }
+
+    /// Slice the window by absolute offsets, widened to char boundaries and
+    /// clamped to the bytes still held
+    pub fn slice(&self, start: usize, end: usize) -> &str {
+        let start = floor_char_boundary(&self.text, start.saturating_sub(self.base));
+        let end = ceil_char_boundary(&self.text, end.saturating_sub(self.base));
+        &self.text[start..end.max(start)]
+    }
+
+    /// Text surrounding an absolute span, `radius` bytes on each side
+    pub fn context(&self, start: usize, end: usize, radius: usize) -> &str {
+        self.slice(start.saturating_sub(radius), end + radius)
+    }
+
+    /// Find `regex` matches starting in `[*cursor, limit)`, as absolute spans.
+    ///
+    /// Matches are found with `find_at` so the bytes before the cursor still
+    /// count as context for anchors such as `\b`. The cursor is advanced past
+    /// every settled match and up to `limit`, because no match can start
+    /// between the last settled match and the limit.
+    pub fn find_settled(&self, regex: &Regex, cursor: &mut usize, limit: usize) -> Vec<(usize, usize)> {
+        self.find_settled_from(regex, cursor, limit, false)
+    }
+
+    /// Like `find_settled`, but the search resumes one char after the start of
+    /// each match, so overlapping occurrences are all reported
+    pub fn find_settled_overlapping(&self, regex: &Regex, cursor: &mut usize, limit: usize) -> Vec<(usize, usize)> {
+        self.find_settled_from(regex, cursor, limit, true)
+    }
+
+    /// Char ending at absolute offset `offset`, if it is still held
+    pub fn char_before(&self, offset: usize) -> Option<char> {
+        let offset = offset.checked_sub(self.base)?;
+        self.text.get(..offset)?.chars().next_back()
+    }
+
+    /// Char starting at absolute offset `offset`, if it is held
+    pub fn char_at(&self, offset: usize) -> Option<char> {
+        let offset = offset.checked_sub(self.base)?;
+        self.text.get(offset..)?.chars().next()
+    }
+
+    fn find_settled_from(&self, regex: &Regex, cursor: &mut usize, limit: usize, overlapping: bool) -> Vec<(usize, usize)> {
+        let mut spans = Vec::new();
+        let mut at = cursor.saturating_sub(self.base).min(self.text.len());
+
+        while let Some(m) = regex.find_at(&self.text, at) {
+            if self.base + m.start() >= limit {
+                break;
+            }
+            spans.push((self.base + m.start(), self.base + m.end()));
+            at = if m.end() > m.start() && !overlapping {
+                m.end()
+            } else {
+                ceil_char_boundary(&self.text, m.start() + 1)
+            };
+            if at >= self.text.len() {
+                break;
+            }
+        }
+
+        *cursor = (self.base + at).max(limit);
+        spans
+    }
+
+    /// Drop the bytes before absolute offset `keep_from` (rounded down to a char boundary)
+    pub fn discard_before(&mut self, keep_from: usize) {
+        let cut = floor_char_boundary(&self.text, keep_from.saturating_sub(self.base));
+        self.base_position = self.base_position.advanced(&self.text[..cut]);
+        self.text.drain(..cut);
+        self.base += cut;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_settled_resumes_across_chunks() {
+        let regex = Regex::new(r"\b\d{9,12}\b").unwrap();
+        let mut window = ChunkWindow::new();
+        let mut cursor = 0;
+
+        window.push("id 12345");
+        let limit = window.settled_limit(13, false);
+        assert!(window.find_settled(&regex, &mut cursor, limit).is_empty());
+        window.discard_before(cursor.saturating_sub(MAX_CHAR_BYTES));
+
+        window.push("67890 end");
+        let limit = window.settled_limit(13, true);
+        assert_eq!(window.find_settled(&regex, &mut cursor, limit), vec![(3, 13)]);
+        assert_eq!(window.slice(3, 13), "1234567890");
+    }
+
+    #[test]
+    fn test_discard_keeps_char_boundaries() {
+        let mut window = ChunkWindow::new();
+        window.push("ééé");
+        window.discard_before(3);
+        assert_eq!(window.base(), 2);
+        assert_eq!(window.text(), "éé");
+    }
+
+    #[test]
+    fn test_locator_tracks_discarded_text() {
+        let mut window = ChunkWindow::new();
+        window.push("first 😀\nsecond");
+        window.discard_before(9);
+        window.push(" line\nthird");
+
+        let mut locator = window.locator();
+        let third = locator.position(window.text().find("third").unwrap());
+        assert_eq!((third.line, third.column), (3, 1));
+        assert_eq!(third.byte_offset, 23);
+        assert_eq!(third.utf16_offset, 21);
+    }
+}
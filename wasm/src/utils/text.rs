@@ -10,3 +10,109 @@ pub fn clean_text(text: &str) -> String {
         .filter(|c| c.is_alphanumeric())
         .collect()
 }
+
+/// Largest char boundary of `text` that is `<= index`
+pub fn floor_char_boundary(text: &str, index: usize) -> usize {
+    if index >= text.len() {
+        return text.len();
+    }
+    let mut index = index;
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Smallest char boundary of `text` that is `>= index`
+pub fn ceil_char_boundary(text: &str, index: usize) -> usize {
+    if index >= text.len() {
+        return text.len();
+    }
+    let mut index = index;
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+/// Position within a document: byte offset, UTF-16 code-unit offset (for JS
+/// string indexing), 1-based line/column, with columns counted in chars, and
+/// the number of words (runs of letters and digits) started before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextPosition {
+    pub byte_offset: usize,
+    pub utf16_offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub words: usize,
+    /// Whether the char before the position is part of a word
+    pub in_word: bool,
+}
+
+impl Default for TextPosition {
+    fn default() -> Self {
+        Self {
+            byte_offset: 0,
+            utf16_offset: 0,
+            line: 1,
+            column: 1,
+            words: 0,
+            in_word: false,
+        }
+    }
+}
+
+impl TextPosition {
+    /// Position reached after reading `text` from this position
+    pub fn advanced(mut self, text: &str) -> Self {
+        for c in text.chars() {
+            self.byte_offset += c.len_utf8();
+            self.utf16_offset += c.len_utf16();
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+            let alphanumeric = c.is_alphanumeric();
+            if alphanumeric && !self.in_word {
+                self.words += 1;
+            }
+            self.in_word = alphanumeric;
+        }
+        self
+    }
+}
+
+/// Converts byte offsets within `text` into document positions.
+///
+/// `origin` is the document position of the first byte of `text`, so the
+/// locator also works for a window into a larger document. Lookups at
+/// increasing offsets resume from the previous one, so locating the sorted
+/// findings of a detector costs a single pass over the text.
+#[derive(Debug)]
+pub struct TextLocator<'a> {
+    text: &'a str,
+    origin: TextPosition,
+    last: (usize, TextPosition),
+}
+
+impl<'a> TextLocator<'a> {
+    pub fn new(text: &'a str, origin: TextPosition) -> Self {
+        Self { text, origin, last: (0, origin) }
+    }
+
+    /// Position of byte `offset` within the located text
+    pub fn position(&mut self, offset: usize) -> TextPosition {
+        let offset = floor_char_boundary(self.text, offset);
+        let (from, start) = if offset >= self.last.0 {
+            self.last
+        } else {
+            (0, self.origin)
+        };
+
+        let position = start.advanced(&self.text[from..offset]);
+        self.last = (offset, position);
+        position
+    }
+}